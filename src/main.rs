@@ -2,9 +2,15 @@ mod runtime;
 mod utils;
 
 use utils::cli::Commands;
-use utils::error::Result;
-use utils::watcher::FileWatcher;
-use std::time::Instant;
+use utils::error::{Result, RvmError};
+use utils::project::requested_version;
+use utils::livereload::LiveReload;
+use utils::watch_events::EventLog;
+use utils::watch_keys::KeyControls;
+use utils::watch_ready::ReadyCheck;
+use utils::watch_tasks::run_project_tasks;
+use utils::watcher::{FileWatcher, WatchOptions};
+use std::time::{Duration, Instant};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -15,16 +21,37 @@ async fn main() -> Result<()> {
     match &cli.command {
         Commands::Add { runtime, version } => match runtime::get_runtime(runtime) {
             Ok(rt) => {
+                let runtime_name = runtime.clone();
                 let version = version.clone();
-                if let Err(e) = tokio::task::spawn_blocking(move || rt.add(version.as_deref()))
-                    .await
-                    .unwrap()
+                if let Err(e) = tokio::task::spawn_blocking(move || {
+                    let version = requested_version(&runtime_name, rt.as_ref(), version)?;
+                    rt.add(version.as_deref())
+                })
+                .await
+                .unwrap()
                 {
                     println!("Error adding {}: {}", runtime, e);
                 }
             }
             Err(e) => println!("Error: {}", e),
         },
+        Commands::Install { locked } => {
+            let locked = *locked;
+            if let Err(e) = tokio::task::spawn_blocking(move || utils::install::handle_install_command(locked))
+                .await
+                .unwrap()
+            {
+                println!("Error installing project runtimes: {}", e);
+            }
+        }
+        Commands::Lock => {
+            if let Err(e) = tokio::task::spawn_blocking(utils::lock::handle_lock_command)
+                .await
+                .unwrap()
+            {
+                println!("Error locking project runtimes: {}", e);
+            }
+        }
         Commands::Remove { runtime, version } => match runtime::get_runtime(runtime) {
             Ok(rt) => {
                 let version = version.clone();
@@ -74,19 +101,79 @@ async fn main() -> Result<()> {
         },
         Commands::Use { runtime, version } => match runtime::get_runtime(runtime) {
             Ok(rt) => {
-                let version_clone = version.clone();
-                if let Err(e) = tokio::task::spawn_blocking(move || rt.use_version(&version_clone))
-                    .await
-                    .unwrap()
+                let runtime_name = runtime.clone();
+                let version = version.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || {
+                    let version = requested_version(&runtime_name, rt.as_ref(), version)?
+                        .ok_or_else(|| RvmError::MissingArgument("version".to_string()))?;
+                    rt.use_version(&version)
+                })
+                .await
+                .unwrap()
                 {
-                    println!("Error using {} {}: {}", runtime, version, e);
+                    println!("Error using {}: {}", runtime, e);
                 }
             }
             Err(e) => println!("Error: {}", e),
         },
-        Commands::Watch { args } => {
-            let mut watcher = FileWatcher::new(args.clone())?;
-            watcher.start().await?;
+        Commands::Watch(args) => {
+            let options = WatchOptions {
+                debounce: Duration::from_millis(args.debounce),
+                delay: args.delay,
+                separated: args.command_follows_separator(),
+                ignore: args.ignore.clone(),
+                paths: args.watch_paths.clone(),
+                extensions: args.ext.clone(),
+                include: args.include.clone(),
+                stop_signal: args.signal,
+                grace_period: Duration::from_millis(args.grace_period),
+                build: args.build.clone(),
+                restart: args.restart,
+                max_retries: args.max_retries,
+                exit_on_success: args.exit_on_success,
+                poll: args.poll.map(Duration::from_millis),
+                profile: args.profile.clone(),
+                env: args.env.clone(),
+                ready: ReadyCheck::from_options(
+                    args.ready_port,
+                    args.ready_log.as_deref(),
+                    args.ready_url.as_deref(),
+                )?,
+                ready_timeout: Duration::from_millis(args.ready_timeout),
+                on_change: args.on_change.clone(),
+                on_exit: args.on_exit.clone(),
+            };
+            // Restores the terminal when dropped at the end of this arm
+            let controls = if args.no_keys { None } else { KeyControls::enable() };
+            let livereload = match args.livereload {
+                Some(port) => Some(LiveReload::start(port).await?),
+                None => None,
+            };
+            // JSON is the only format so far
+            let event_log = args.events_file.clone().map(EventLog::open);
+            let result = if args.command.is_empty() {
+                run_project_tasks(options, controls.as_ref(), livereload, event_log.clone()).await
+            } else {
+                async {
+                    let mut watcher = FileWatcher::new(args.command.clone(), options)?;
+                    if let Some(controls) = &controls {
+                        watcher.set_keys(controls.subscribe());
+                    }
+                    if let Some(server) = livereload {
+                        watcher.set_livereload(server);
+                    }
+                    if let Some(event_log) = &event_log {
+                        watcher.set_event_log(event_log.clone());
+                    }
+                    watcher.start().await
+                }
+                .await
+            };
+            // The last events, such as the final exit, are written before rvm exits
+            if let Some(event_log) = &event_log {
+                event_log.close().await;
+            }
+            result?;
         }
         Commands::List { args } => {
             let args_clone = args.clone();
@@ -0,0 +1,467 @@
+use super::Runtime;
+use crate::utils::download::{
+    Platform, download_with_progress, extract_zip, find_checksum, make_executable, verify_sha256,
+};
+use crate::utils::error::{Result, RvmError};
+use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{VersionReq, select_newest};
+use crate::utils::ui::{display_error, display_step, display_success};
+use crate::utils::version::{
+    VersionInfo, apply_version_to_current_session, detect_channel, extract_major_minor,
+    find_installed_version, get_runtime_home, group_versions_by_channel, is_version_installed,
+    list_installed_versions, remove_version, resolve_installed_version,
+};
+use reqwest::blocking;
+use serde::Deserialize;
+use std::path::Path;
+
+/// Bun runtime manager
+pub struct BunRuntime;
+
+/// Release tags look like "bun-v1.1.38", installed versions drop the prefix
+const TAG_PREFIX: &str = "bun-";
+
+/// Releases are fetched 100 per page, enough pages to cover every 1.x release
+const RELEASE_PAGES: u32 = 3;
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    prerelease: bool,
+}
+
+impl BunRuntime {
+    /// Parse the GitHub releases API response, keeping only versioned releases (not "canary")
+    fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<GitHubRelease>> {
+        let releases: Vec<GitHubRelease> = serde_json::from_value(json_data.clone())?;
+        Ok(releases
+            .into_iter()
+            .filter_map(|release| {
+                let version = release.tag_name.strip_prefix(TAG_PREFIX)?.to_string();
+                Some(GitHubRelease { tag_name: version, prerelease: release.prerelease })
+            })
+            .collect())
+    }
+
+    /// Build name for the platform (e.g. "bun-linux-x64-musl-baseline"). The baseline x64 build
+    /// runs on CPUs without AVX2
+    fn build_name(platform: &Platform) -> Result<String> {
+        let cpu = match platform.arch {
+            "x64" => "x64",
+            "arm64" => "aarch64",
+            arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        };
+        let musl = if platform.musl { "-musl" } else { "" };
+        let baseline = if platform.baseline { "-baseline" } else { "" };
+        Ok(format!("bun-linux-{}{}{}", cpu, musl, baseline))
+    }
+
+    /// Read the Bun version from package.json's packageManager (e.g. "bun@1.1.38")
+    fn package_json_version(path: &Path) -> Result<Option<String>> {
+        let content = std::fs::read_to_string(path)?;
+        let package: serde_json::Value = serde_json::from_str(&content)?;
+
+        let version = package
+            .get("packageManager")
+            .and_then(|v| v.as_str())
+            .and_then(|v| v.trim().strip_prefix("bun@"))
+            .map(|v| v.split('+').next().unwrap_or(v).to_string())
+            .filter(|v| !v.is_empty());
+        Ok(version)
+    }
+
+    /// Download, unzip and activate an exact Bun version, optionally verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
+        // Step 2: Check if already installed
+        if is_version_installed("bun", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
+        }
+
+        // Step 3: Download the zip for this platform
+        let platform = Platform::current()?;
+        let build = Self::build_name(&platform)?;
+        let download_url = self.artifact_url(resolved_version, &platform)?;
+
+        display_step(&format!("Downloading Bun {} ({})", resolved_version, build));
+        let bytes = download_with_progress(&download_url)?;
+        if let Some(expected) = sha256 {
+            verify_sha256(&bytes, expected, &download_url)?;
+        }
+
+        // The archive holds a bun-linux-<target>/bun directory, the executable goes into bin
+        display_step("Extracting archive");
+        let runtime_home = get_runtime_home("bun")?;
+        let temp_dir = runtime_home.join("temp");
+        std::fs::create_dir_all(&temp_dir)?;
+        let extracted = extract_zip(&bytes, &temp_dir).and_then(|()| {
+            let bin_dir = runtime_home.join(resolved_version).join("bin");
+            std::fs::create_dir_all(&bin_dir)?;
+            std::fs::rename(temp_dir.join(&build).join("bun"), bin_dir.join("bun"))?;
+            Ok(bin_dir)
+        });
+        std::fs::remove_dir_all(&temp_dir)?;
+        let bin_dir = match extracted {
+            Ok(bin_dir) => bin_dir,
+            Err(e) => {
+                // Don't leave a half installed version behind
+                let _ = std::fs::remove_dir_all(runtime_home.join(resolved_version));
+                return Err(e);
+            }
+        };
+        make_executable(&bin_dir.join("bun"))?;
+
+        // bunx is bun itself, started under another name
+        std::os::unix::fs::symlink("bun", bin_dir.join("bunx"))?;
+        display_success("Archive extracted successfully");
+
+        // Step 4: Set as default
+        set_default_runtime("bun", resolved_version)?;
+
+        // Step 5: Automatically reload profile
+        reload_profile()?;
+
+        display_success(&format!("Bun {} installation completed successfully!", resolved_version));
+        Ok(())
+    }
+}
+
+impl Runtime for BunRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding Bun version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from GitHub releases API");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked Bun version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        Ok(format!(
+            "https://github.com/oven-sh/bun/releases/download/{}{}/{}.zip",
+            TAG_PREFIX,
+            resolved_version,
+            Self::build_name(platform)?
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        let sums_url = format!(
+            "https://github.com/oven-sh/bun/releases/download/{}{}/SHASUMS256.txt",
+            TAG_PREFIX, resolved_version
+        );
+        display_step(&format!("Fetching the checksums of Bun {}", resolved_version));
+        let response = blocking::get(&sums_url)?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch Bun checksums: {}",
+                response.status()
+            )));
+        }
+
+        let url = self.artifact_url(resolved_version, platform)?;
+        let filename = url.rsplit('/').next().unwrap_or_default();
+        Ok(find_checksum(&response.text()?, filename))
+    }
+
+    fn remove(&self, version: Option<&str>) -> Result<()> {
+        match version {
+            Some(v) => {
+                display_step(&format!("Removing Bun version: {}", v));
+
+                // Check if version is installed
+                if !is_version_installed("bun", v)? {
+                    display_error(&format!("Bun {} is not installed", v));
+                    return Err(RvmError::VersionNotFound(v.to_string()));
+                }
+
+                // Remove from PATH in profile
+                remove_runtime_from_path("bun", v)?;
+
+                // Remove from filesystem
+                remove_version("bun", v)?;
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Bun {} removed successfully", v));
+            }
+            None => {
+                display_step("Removing all Bun versions");
+
+                // Get list of installed versions
+                let installed_versions = list_installed_versions("bun")?;
+
+                if installed_versions.is_empty() {
+                    display_success("No Bun versions are installed");
+                    return Ok(());
+                }
+
+                display_step(&format!("Found {} Bun versions to remove", installed_versions.len()));
+
+                // Remove each version
+                for version in &installed_versions {
+                    display_step(&format!("Removing Bun {}", version));
+                    remove_runtime_from_path("bun", version)?;
+                    remove_version("bun", version)?;
+                }
+
+                // Clean up the entire bun directory if it's empty
+                let runtime_home = get_runtime_home("bun")?;
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty Bun directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty Bun directory");
+                }
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Removed all {} Bun versions successfully", installed_versions.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating Bun to latest version");
+
+        // Get the latest version from the API
+        let latest_version = self.resolve_version("latest")?;
+        display_success(&format!("Latest Bun version is: {}", latest_version));
+
+        // Check if it's already installed
+        if is_version_installed("bun", &latest_version)? {
+            display_success(&format!("Bun {} is already installed", latest_version));
+
+            // Set it as default if it's not already
+            display_step("Ensuring latest version is set as default");
+            set_default_runtime("bun", &latest_version)?;
+            reload_profile()?;
+
+            display_success("Bun is already up to date");
+            return Ok(());
+        }
+
+        // Install the latest version
+        display_step(&format!("Installing Bun {}", latest_version));
+        self.install_resolved(&latest_version, None)?;
+
+        display_success(&format!("Successfully updated Bun to {}", latest_version));
+        Ok(())
+    }
+
+    fn prune(&self, keep_version: &str) -> Result<()> {
+        display_step(&format!("Pruning Bun versions (keeping {})", keep_version));
+
+        // Resolve the version to keep (e.g., "1.1" -> "v1.1.38")
+        let resolved_keep_version = resolve_installed_version("bun", keep_version)?;
+        display_success(&format!("Will keep Bun version: {}", resolved_keep_version));
+
+        // Get list of all installed versions
+        let installed_versions = list_installed_versions("bun")?;
+
+        if installed_versions.is_empty() {
+            display_success("No Bun versions are installed");
+            return Ok(());
+        }
+
+        // Filter out the version to keep
+        let versions_to_remove: Vec<String> = installed_versions
+            .into_iter()
+            .filter(|v| *v != resolved_keep_version)
+            .collect();
+
+        if versions_to_remove.is_empty() {
+            display_success(&format!("Only {} is installed, nothing to prune", resolved_keep_version));
+            return Ok(());
+        }
+
+        display_step(&format!("Found {} Bun versions to remove", versions_to_remove.len()));
+
+        // Remove each version except the one to keep
+        for version in &versions_to_remove {
+            display_step(&format!("Removing Bun {}", version));
+
+            // Remove from PATH in profile
+            remove_runtime_from_path("bun", version)?;
+
+            // Remove from filesystem
+            remove_version("bun", version)?;
+        }
+
+        // Ensure the version to keep is set as default
+        display_step(&format!("Setting {} as default", resolved_keep_version));
+        set_default_runtime("bun", &resolved_keep_version)?;
+
+        // Reload profile to apply changes
+        reload_profile()?;
+
+        display_success(&format!("Pruned {} Bun versions, kept {}", versions_to_remove.len(), resolved_keep_version));
+        Ok(())
+    }
+
+    fn set_default(&self, version: &str) -> Result<()> {
+        display_step(&format!("Setting Bun {} as default", version));
+
+        // Resolve version first (e.g., "1.1" -> "v1.1.38")
+        let resolved_version = resolve_installed_version("bun", version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Set as default
+        set_default_runtime("bun", &resolved_version)?;
+
+        // Reload profile
+        reload_profile()?;
+
+        // Force reload current environment
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("bun", &resolved_version, "bun")?;
+
+        display_success(&format!("Bun {} is now the default version", resolved_version));
+        Ok(())
+    }
+
+    fn use_version(&self, version: &str) -> Result<()> {
+        display_step(&format!("Switching to Bun {} for current session", version));
+
+        // Resolve version first (e.g., "1.1" -> "v1.1.38")
+        let resolved_version = resolve_installed_version("bun", version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Apply version to current session
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("bun", &resolved_version, "bun")?;
+
+        display_success(&format!("Bun {} is now active in current session", resolved_version));
+        println!("💡 This change is temporary. To make it permanent, run:");
+        println!("   rvm set bun {}", version);
+        Ok(())
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        list_installed_versions("bun")
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        find_installed_version("bun", version_input)
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        // Convert releases to VersionInfo with channel detection
+        let version_infos = releases
+            .into_iter()
+            .filter_map(|release| {
+                let major_minor = extract_major_minor(&release.tag_name)?;
+                Some(VersionInfo {
+                    channel: detect_channel(&release.tag_name, false),
+                    version: release.tag_name,
+                    major_minor,
+                })
+            })
+            .collect();
+
+        display_success("Successfully retrieved and organized Bun versions");
+        Ok(group_versions_by_channel(version_infos))
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        display_step("Fetching Bun versions from GitHub releases API");
+
+        let client = blocking::Client::new();
+        let mut releases = Vec::new();
+
+        for page in 1..=RELEASE_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/oven-sh/bun/releases?per_page=100&page={}",
+                page
+            );
+            let response = client
+                .get(&url)
+                .header("User-Agent", "rvm-rust/1.0.0")
+                .send()?;
+
+            if !response.status().is_success() {
+                return Err(RvmError::HttpError(format!(
+                    "Failed to fetch Bun releases: {}",
+                    response.status()
+                )));
+            }
+
+            let page_releases: Vec<serde_json::Value> = response.json()?;
+            let last_page = page_releases.len() < 100;
+            releases.extend(page_releases);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(serde_json::Value::Array(releases))
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        let json_data = self.fetch_available_versions()?;
+        let available_versions = Self::parse_api_response(&json_data)?;
+
+        match version_input {
+            "latest" => {
+                // Newest stable release by version, the API lists releases by publish date
+                let stable = available_versions
+                    .iter()
+                    .filter(|r| !r.prerelease)
+                    .map(|r| r.tag_name.as_str());
+                select_newest(stable, "*")
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::VersionNotFound("latest".to_string()))
+            }
+            v => {
+                // Exact version, partial version or semver range (e.g., "1.1.38", "1.1", "^1"),
+                // newest matching release wins
+                let v = v.strip_prefix(TAG_PREFIX).unwrap_or(v);
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                select_newest(available_versions.iter().map(|r| r.tag_name.as_str()), v)
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
+            }
+        }
+    }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest directory wins, .bun-version > package.json
+        for dir in start.ancestors() {
+            let bun_version = dir.join(".bun-version");
+            if bun_version.is_file()
+                && let Some(version) = read_version_file(&bun_version)?
+            {
+                return Ok(Some(VersionHint { version, source: bun_version }));
+            }
+
+            let package_json = dir.join("package.json");
+            if package_json.is_file()
+                && let Some(version) = Self::package_json_version(&package_json)?
+            {
+                return Ok(Some(VersionHint { version, source: package_json }));
+            }
+        }
+        Ok(None)
+    }
+}
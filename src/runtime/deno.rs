@@ -0,0 +1,529 @@
+use super::Runtime;
+use crate::utils::download::{
+    Platform, download_with_progress, extract_zip, make_executable, verify_sha256,
+};
+use crate::utils::error::{Result, RvmError};
+use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{VersionReq, select_newest};
+use crate::utils::ui::{display_error, display_step, display_success};
+use crate::utils::version::{
+    VersionInfo, apply_version_to_current_session, detect_channel, extract_major_minor,
+    find_installed_version, get_runtime_home, group_versions_by_channel, is_version_installed,
+    list_installed_versions, remove_version, resolve_installed_version,
+};
+use reqwest::{StatusCode, blocking};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Deno runtime manager
+pub struct DenoRuntime;
+
+/// Canary builds are installed as "canary-<commit>"
+const CANARY_PREFIX: &str = "canary-";
+
+/// Releases are fetched 100 per page, enough pages to cover the last few years
+const RELEASE_PAGES: u32 = 3;
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    prerelease: bool,
+}
+
+impl DenoRuntime {
+    /// Parse the GitHub releases API response
+    fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<GitHubRelease>> {
+        let releases: Vec<GitHubRelease> = serde_json::from_value(json_data.clone())?;
+        Ok(releases)
+    }
+
+    /// Rust target triple Deno builds are published for (e.g. "x86_64-unknown-linux-gnu")
+    fn target_triple(platform: &Platform) -> Result<String> {
+        let cpu = match platform.arch {
+            "x64" => "x86_64",
+            "arm64" => "aarch64",
+            arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        };
+        let libc = if platform.musl { "musl" } else { "gnu" };
+        Ok(format!("{}-unknown-linux-{}", cpu, libc))
+    }
+
+    fn is_canary(version: &str) -> bool {
+        version.starts_with(CANARY_PREFIX)
+    }
+
+    /// Commit of the newest canary build, as an installable version (e.g. "canary-1a2b3c...")
+    fn latest_canary(&self) -> Result<String> {
+        let response = blocking::get("https://dl.deno.land/canary-latest.txt")?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch the latest Deno canary: {}",
+                response.status()
+            )));
+        }
+
+        let commit = response.text()?.trim().to_string();
+        if commit.is_empty() {
+            return Err(RvmError::VersionNotFound("canary".to_string()));
+        }
+        Ok(format!("{}{}", CANARY_PREFIX, commit))
+    }
+
+    /// Resolve version input against installed versions, including the "stable" and "canary"
+    /// channels (e.g., "canary" -> the most recently installed canary build)
+    fn resolve_installed(&self, version_input: &str) -> Result<String> {
+        let installed_versions = list_installed_versions("deno")?;
+
+        let newest = match version_input {
+            "latest" | "stable" => installed_versions
+                .into_iter()
+                .find(|version| !Self::is_canary(version)),
+            "canary" => {
+                // Canary commits have no order of their own, the last one installed wins
+                let runtime_home = get_runtime_home("deno")?;
+                installed_versions
+                    .into_iter()
+                    .filter(|version| Self::is_canary(version))
+                    .max_by_key(|version| {
+                        std::fs::metadata(runtime_home.join(version))
+                            .and_then(|metadata| metadata.modified())
+                            .ok()
+                    })
+            }
+            // Exact versions, partial versions and ranges resolve the same way for every runtime
+            _ => return resolve_installed_version("deno", version_input),
+        };
+
+        newest.ok_or_else(|| RvmError::VersionNotFound(version_input.to_string()))
+    }
+
+    /// Read the Deno version from deno.json's engines.deno
+    fn deno_json_version(path: &Path) -> Result<Option<String>> {
+        let content = std::fs::read_to_string(path)?;
+        let config: serde_json::Value = serde_json::from_str(&content)?;
+
+        let version = config
+            .pointer("/engines/deno")
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        Ok(version)
+    }
+
+    /// Download, unzip and activate an exact Deno version, optionally verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
+        // Step 2: Check if already installed
+        if is_version_installed("deno", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
+        }
+
+        // Step 3: Download the zip for this platform
+        let platform = Platform::current()?;
+        let target = Self::target_triple(&platform)?;
+        let download_url = self.artifact_url(resolved_version, &platform)?;
+
+        display_step(&format!("Downloading Deno {} for {}", resolved_version, target));
+        let bytes = download_with_progress(&download_url).inspect_err(|_| {
+            display_error(&format!("No Deno {} build found for {}", resolved_version, target));
+        })?;
+        if let Some(expected) = sha256 {
+            verify_sha256(&bytes, expected, &download_url)?;
+        }
+
+        // The archive holds just the deno executable
+        display_step("Extracting archive");
+        let version_dir = get_runtime_home("deno")?.join(resolved_version);
+        let bin_dir = version_dir.join("bin");
+        std::fs::create_dir_all(&bin_dir)?;
+        if let Err(e) = extract_zip(&bytes, &bin_dir) {
+            // Don't leave a half installed version behind
+            let _ = std::fs::remove_dir_all(&version_dir);
+            return Err(e);
+        }
+        make_executable(&bin_dir.join("deno"))?;
+        display_success("Archive extracted successfully");
+
+        // Step 4: Set as default
+        set_default_runtime("deno", resolved_version)?;
+
+        // Step 5: Automatically reload profile
+        reload_profile()?;
+
+        display_success(&format!("Deno {} installation completed successfully!", resolved_version));
+        Ok(())
+    }
+}
+
+impl Runtime for DenoRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding Deno version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from Deno releases");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked Deno version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let filename = format!("deno-{}.zip", Self::target_triple(platform)?);
+        Ok(match resolved_version.strip_prefix(CANARY_PREFIX) {
+            Some(commit) => format!("https://dl.deno.land/canary/{}/{}", commit, filename),
+            None => format!(
+                "https://github.com/denoland/deno/releases/download/{}/{}",
+                resolved_version, filename
+            ),
+        })
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        let checksum_url = format!("{}.sha256sum", self.artifact_url(resolved_version, platform)?);
+        display_step(&format!(
+            "Fetching the checksum of Deno {} ({})",
+            resolved_version,
+            platform.key()
+        ));
+        let response = blocking::get(&checksum_url)?;
+        // Older releases have no .sha256sum files, their first download is hashed instead
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch the Deno checksum: {}",
+                response.status()
+            )));
+        }
+
+        // "<sha256>  <path of the zip on the build machine>"
+        let text = response.text()?;
+        let sha256 = text.split_whitespace().next().unwrap_or_default();
+        if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(RvmError::HttpError(format!("Unexpected checksum in {}", checksum_url)));
+        }
+        Ok(Some(sha256.to_ascii_lowercase()))
+    }
+
+    fn remove(&self, version: Option<&str>) -> Result<()> {
+        match version {
+            Some(v) => {
+                display_step(&format!("Removing Deno version: {}", v));
+
+                // Check if version is installed
+                if !is_version_installed("deno", v)? {
+                    display_error(&format!("Deno {} is not installed", v));
+                    return Err(RvmError::VersionNotFound(v.to_string()));
+                }
+
+                // Remove from PATH in profile
+                remove_runtime_from_path("deno", v)?;
+
+                // Remove from filesystem
+                remove_version("deno", v)?;
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Deno {} removed successfully", v));
+            }
+            None => {
+                display_step("Removing all Deno versions");
+
+                // Get list of installed versions
+                let installed_versions = list_installed_versions("deno")?;
+
+                if installed_versions.is_empty() {
+                    display_success("No Deno versions are installed");
+                    return Ok(());
+                }
+
+                display_step(&format!("Found {} Deno versions to remove", installed_versions.len()));
+
+                // Remove each version
+                for version in &installed_versions {
+                    display_step(&format!("Removing Deno {}", version));
+                    remove_runtime_from_path("deno", version)?;
+                    remove_version("deno", version)?;
+                }
+
+                // Clean up the entire deno directory if it's empty
+                let runtime_home = get_runtime_home("deno")?;
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty Deno directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty Deno directory");
+                }
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Removed all {} Deno versions successfully", installed_versions.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating Deno to latest version");
+
+        // Get the latest stable version from the API
+        let latest_version = self.resolve_version("latest")?;
+        display_success(&format!("Latest Deno version is: {}", latest_version));
+
+        // Check if it's already installed
+        if is_version_installed("deno", &latest_version)? {
+            display_success(&format!("Deno {} is already installed", latest_version));
+
+            // Set it as default if it's not already
+            display_step("Ensuring latest version is set as default");
+            set_default_runtime("deno", &latest_version)?;
+            reload_profile()?;
+
+            display_success("Deno is already up to date");
+            return Ok(());
+        }
+
+        // Install the latest version
+        display_step(&format!("Installing Deno {}", latest_version));
+        self.install_resolved(&latest_version, None)?;
+
+        display_success(&format!("Successfully updated Deno to {}", latest_version));
+        Ok(())
+    }
+
+    fn prune(&self, keep_version: &str) -> Result<()> {
+        display_step(&format!("Pruning Deno versions (keeping {})", keep_version));
+
+        // Resolve the version to keep (e.g., "2" -> "v2.1.4", "canary" -> "canary-<commit>")
+        let resolved_keep_version = self.resolve_installed(keep_version)?;
+        display_success(&format!("Will keep Deno version: {}", resolved_keep_version));
+
+        // Get list of all installed versions
+        let installed_versions = list_installed_versions("deno")?;
+
+        if installed_versions.is_empty() {
+            display_success("No Deno versions are installed");
+            return Ok(());
+        }
+
+        // Filter out the version to keep
+        let versions_to_remove: Vec<String> = installed_versions
+            .into_iter()
+            .filter(|v| *v != resolved_keep_version)
+            .collect();
+
+        if versions_to_remove.is_empty() {
+            display_success(&format!("Only {} is installed, nothing to prune", resolved_keep_version));
+            return Ok(());
+        }
+
+        display_step(&format!("Found {} Deno versions to remove", versions_to_remove.len()));
+
+        // Remove each version except the one to keep
+        for version in &versions_to_remove {
+            display_step(&format!("Removing Deno {}", version));
+
+            // Remove from PATH in profile
+            remove_runtime_from_path("deno", version)?;
+
+            // Remove from filesystem
+            remove_version("deno", version)?;
+        }
+
+        // Ensure the version to keep is set as default
+        display_step(&format!("Setting {} as default", resolved_keep_version));
+        set_default_runtime("deno", &resolved_keep_version)?;
+
+        // Reload profile to apply changes
+        reload_profile()?;
+
+        display_success(&format!("Pruned {} Deno versions, kept {}", versions_to_remove.len(), resolved_keep_version));
+        Ok(())
+    }
+
+    fn set_default(&self, version: &str) -> Result<()> {
+        display_step(&format!("Setting Deno {} as default", version));
+
+        // Resolve version first (e.g., "2" -> "v2.1.4", "canary" -> "canary-<commit>")
+        let resolved_version = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Set as default
+        set_default_runtime("deno", &resolved_version)?;
+
+        // Reload profile
+        reload_profile()?;
+
+        // Force reload current environment
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("deno", &resolved_version, "deno")?;
+
+        display_success(&format!("Deno {} is now the default version", resolved_version));
+        Ok(())
+    }
+
+    fn use_version(&self, version: &str) -> Result<()> {
+        display_step(&format!("Switching to Deno {} for current session", version));
+
+        // Resolve version first (e.g., "2" -> "v2.1.4", "canary" -> "canary-<commit>")
+        let resolved_version = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Apply version to current session
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("deno", &resolved_version, "deno")?;
+
+        display_success(&format!("Deno {} is now active in current session", resolved_version));
+        println!("💡 This change is temporary. To make it permanent, run:");
+        println!("   rvm set deno {}", version);
+        Ok(())
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        list_installed_versions("deno")
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        match version_input {
+            "latest" | "stable" | "canary" => match self.resolve_installed(version_input) {
+                Ok(installed) => Ok(Some(installed)),
+                Err(RvmError::VersionNotFound(_)) => Ok(None),
+                Err(e) => Err(e),
+            },
+            _ => find_installed_version("deno", version_input),
+        }
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        // Convert releases to VersionInfo with channel detection (e.g. "v2.0.0-rc.1" -> RC)
+        let version_infos = releases
+            .into_iter()
+            .filter_map(|release| {
+                let major_minor = extract_major_minor(&release.tag_name)?;
+                Some(VersionInfo {
+                    channel: detect_channel(&release.tag_name, false),
+                    version: release.tag_name,
+                    major_minor,
+                })
+            })
+            .collect();
+
+        let mut result = group_versions_by_channel(version_infos);
+
+        // Canary builds are published per commit outside of GitHub releases
+        display_step("Fetching the latest Deno canary build");
+        let canary = self.latest_canary()?;
+        result.push(String::new());
+        result.push("=== Canary Builds ===".to_string());
+        result.push(format!("canary: {} (install with `rvm add deno canary`)", canary));
+
+        display_success("Successfully retrieved and organized Deno versions");
+        Ok(result)
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        display_step("Fetching Deno versions from GitHub releases API");
+
+        let client = blocking::Client::new();
+        let mut releases = Vec::new();
+
+        for page in 1..=RELEASE_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/denoland/deno/releases?per_page=100&page={}",
+                page
+            );
+            let response = client
+                .get(&url)
+                .header("User-Agent", "rvm-rust/1.0.0")
+                .send()?;
+
+            if !response.status().is_success() {
+                return Err(RvmError::HttpError(format!(
+                    "Failed to fetch Deno releases: {}",
+                    response.status()
+                )));
+            }
+
+            let page_releases: Vec<serde_json::Value> = response.json()?;
+            let last_page = page_releases.len() < 100;
+            releases.extend(page_releases);
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(serde_json::Value::Array(releases))
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        match version_input {
+            "canary" => return self.latest_canary(),
+            // A specific canary build (e.g. from rvm.lock) is already exact
+            v if Self::is_canary(v) => return Ok(v.to_string()),
+            _ => {}
+        }
+
+        let json_data = self.fetch_available_versions()?;
+        let available_versions = Self::parse_api_response(&json_data)?;
+
+        match version_input {
+            "latest" | "stable" => {
+                // Newest stable release by version, the API lists releases by publish date
+                let stable = available_versions
+                    .iter()
+                    .filter(|r| !r.prerelease)
+                    .map(|r| r.tag_name.as_str());
+                select_newest(stable, "*")
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::VersionNotFound(version_input.to_string()))
+            }
+            v => {
+                // Exact version, partial version or semver range (e.g., "2.1.4", "v2.1", "^1.46"),
+                // newest matching release wins
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                select_newest(available_versions.iter().map(|r| r.tag_name.as_str()), v)
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
+            }
+        }
+    }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest directory wins, .dvmrc > deno.json
+        for dir in start.ancestors() {
+            let dvmrc = dir.join(".dvmrc");
+            if dvmrc.is_file()
+                && let Some(version) = read_version_file(&dvmrc)?
+            {
+                return Ok(Some(VersionHint { version, source: dvmrc }));
+            }
+
+            let deno_json = dir.join("deno.json");
+            if deno_json.is_file()
+                && let Some(version) = Self::deno_json_version(&deno_json)?
+            {
+                return Ok(Some(VersionHint { version, source: deno_json }));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -1,15 +1,18 @@
 use super::Runtime;
-use crate::utils::download::{download_and_extract, get_architecture};
+use crate::utils::download::{Platform, download_and_extract};
 use crate::utils::error::{Result, RvmError};
 use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::VersionHint;
+use crate::utils::semver::{Version, VersionReq, select_newest};
 use crate::utils::ui::{display_error, display_step, display_success};
 use crate::utils::version::{
-    apply_version_to_current_session, get_runtime_home, is_version_installed,
-    list_installed_versions, remove_version, resolve_installed_version,
+    apply_version_to_current_session, compare_versions, find_installed_version, get_runtime_home,
+    is_version_installed, list_installed_versions, remove_version, resolve_installed_version,
 };
 use reqwest::blocking;
 use scraper::{Html, Selector};
 use serde::Deserialize;
+use std::path::Path;
 
 /// Go runtime manager
 pub struct GoRuntime;
@@ -20,51 +23,105 @@ struct GoRelease {
     stable: bool,
 }
 
+/// A release in the JSON listing of go.dev/dl, which carries the checksums
+#[derive(Deserialize)]
+struct GoDownloads {
+    version: String,
+    files: Vec<GoFile>,
+}
+
+#[derive(Deserialize)]
+struct GoFile {
+    filename: String,
+    sha256: String,
+}
+
 impl GoRuntime {
     /// Parse the API response into Go specific release data
     fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<GoRelease>> {
         let releases: Vec<GoRelease> = serde_json::from_value(json_data.clone())?;
         Ok(releases)
     }
-}
 
-impl Runtime for GoRuntime {
-    fn add(&self, version: Option<&str>) -> Result<()> {
-        let version_str = version.unwrap_or("latest");
-        display_step(&format!("Adding Go version: {}", version_str));
-
-        // Step 1: Parse and resolve version
-        display_step("Resolving version from Go API");
-        let resolved_version = self.resolve_version(version_str)?;
-        display_success(&format!("Resolved to version: {}", resolved_version));
+    /// Convert a release name to the storage naming (e.g. go1.23.11 -> v1.23.11)
+    fn storage_version(release: &str) -> String {
+        format!("v{}", release.strip_prefix("go").unwrap_or(release))
+    }
 
-        // Step 2: Check if already installed (has built-in messaging)
-        if is_version_installed("go", &resolved_version)? {
-            return Err(RvmError::VersionAlreadyInstalled(resolved_version));
+    /// Read the `go` and `toolchain` directives from a go.mod or go.work file
+    fn read_go_directives(path: &Path) -> Result<(Option<String>, Option<String>)> {
+        let content = std::fs::read_to_string(path)?;
+        let mut go_directive = None;
+        let mut toolchain_directive = None;
+
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            let mut parts = line.split_whitespace();
+            match (parts.next(), parts.next()) {
+                (Some("go"), Some(version)) => go_directive = Some(version.to_string()),
+                // "toolchain default" means no toolchain preference
+                (Some("toolchain"), Some(toolchain)) if toolchain != "default" => {
+                    toolchain_directive = Some(toolchain.to_string())
+                }
+                _ => {}
+            }
         }
 
-        // Step 3: Download and extract (has built-in messaging)
-        let arch = get_architecture()?;
-        let go_arch = match arch {
-            "x64" => "amd64",
-            "arm64" => "arm64",
-            _ => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        Ok((go_directive, toolchain_directive))
+    }
+
+    /// Oldest stable release satisfying a go directive (e.g. "1.22" -> go1.22.0, "1.20" -> go1.20).
+    /// Installed releases are tried first, so a project that is set up needs no network
+    fn minimum_release_for(&self, go_version: &str) -> Result<String> {
+        let minimum = Version::parse(go_version)
+            .ok_or_else(|| RvmError::InvalidVersionFormat(go_version.to_string()))?;
+        let oldest_satisfying = |releases: Vec<String>| {
+            releases
+                .into_iter()
+                .filter_map(|release| {
+                    let version = Version::parse(&release)?;
+                    (!version.is_prerelease() && version >= minimum).then_some((version, release))
+                })
+                .min_by(|a, b| a.0.cmp(&b.0))
+                .map(|(_, release)| release)
         };
 
-        let filename = format!("{}.linux-{}.tar.gz", resolved_version, go_arch);
-        let download_url = format!("https://go.dev/dl/{}", filename);
+        // Installed releases are stored as v1.23.11
+        let installed = list_installed_versions("go")?
+            .into_iter()
+            .map(|version| format!("go{}", version.trim_start_matches('v')))
+            .collect();
+        if let Some(release) = oldest_satisfying(installed) {
+            return Ok(release);
+        }
 
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+        let stable = releases.into_iter().filter(|r| r.stable).map(|r| r.version).collect();
+        oldest_satisfying(stable)
+            .ok_or_else(|| RvmError::VersionNotFound(format!("go {}", go_version)))
+    }
+
+    /// Download, extract and activate an exact Go release (e.g. go1.23.11), optionally
+    /// verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
         // Convert from go1.23.11 to v1.23.11 for storage
-        let storage_version = resolved_version
-            .strip_prefix("go")
-            .unwrap_or(&resolved_version);
-        let storage_version = format!("v{}", storage_version);
+        let storage_version = Self::storage_version(resolved_version);
+
+        // Step 2: Check if already installed (has built-in messaging)
+        if is_version_installed("go", &storage_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(storage_version));
+        }
+
+        // Step 3: Download and extract (has built-in messaging)
+        let download_url = self.artifact_url(resolved_version, &Platform::current()?)?;
 
         download_and_extract(
             &download_url,
             "go",
             &storage_version,
             &["bin/go", "bin/gofmt"], // Make go and gofmt executable
+            sha256,
         )?;
 
         // Step 4: Set as default (has built-in messaging)
@@ -79,6 +136,65 @@ impl Runtime for GoRuntime {
         ));
         Ok(())
     }
+}
+
+impl Runtime for GoRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding Go version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from Go API");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked Go version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let go_arch = match platform.arch {
+            "x64" => "amd64",
+            "arm64" => "arm64",
+            arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        };
+
+        let filename = format!("{}.linux-{}.tar.gz", resolved_version, go_arch);
+        Ok(format!("https://go.dev/dl/{}", filename))
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        display_step(&format!("Fetching the checksums of {}", resolved_version));
+        let client = blocking::Client::new();
+        let response = client
+            .get("https://go.dev/dl/?mode=json&include=all")
+            .header("User-Agent", "rvm-rust/1.0.0")
+            .send()?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch Go checksums: {}",
+                response.status()
+            )));
+        }
+
+        let url = self.artifact_url(resolved_version, platform)?;
+        let filename = url.rsplit('/').next().unwrap_or_default();
+        let releases: Vec<GoDownloads> = response.json()?;
+        Ok(releases
+            .into_iter()
+            .filter(|release| release.version == resolved_version)
+            .flat_map(|release| release.files)
+            .find(|file| file.filename == filename)
+            .map(|file| file.sha256))
+    }
 
     fn remove(&self, version: Option<&str>) -> Result<()> {
         match version {
@@ -127,17 +243,14 @@ impl Runtime for GoRuntime {
 
                 // Clean up the entire go directory if it's empty
                 let runtime_home = get_runtime_home("go")?;
-                if runtime_home.exists() {
-                    match std::fs::read_dir(&runtime_home) {
-                        Ok(mut entries) => {
-                            if entries.next().is_none() {
-                                display_step("Cleaning up empty Go directory");
-                                std::fs::remove_dir(&runtime_home)?;
-                                display_success("Removed empty Go directory");
-                            }
-                        }
-                        Err(_) => {} // Directory doesn't exist or can't read, skip cleanup
-                    }
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty Go directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty Go directory");
                 }
 
                 // Reload profile to apply changes
@@ -294,6 +407,10 @@ impl Runtime for GoRuntime {
         list_installed_versions("go")
     }
 
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        find_installed_version("go", version_input)
+    }
+
     fn list_available(&self) -> Result<Vec<String>> {
         let json_data = self.fetch_available_versions()?;
         let releases = Self::parse_api_response(&json_data)?;
@@ -309,10 +426,9 @@ impl Runtime for GoRuntime {
         for release in releases {
             let version = &release.version;
             // Convert go1.24.5 to v1.24.5 for display
-            let display_version = if version.starts_with("go") {
-                format!("v{}", &version[2..])
-            } else {
-                version.clone()
+            let display_version = match version.strip_prefix("go") {
+                Some(stripped) => format!("v{}", stripped),
+                None => version.clone(),
             };
 
             if release.stable {
@@ -329,14 +445,14 @@ impl Runtime for GoRuntime {
             let mut major_minor_groups: std::collections::BTreeMap<String, Vec<String>> =
                 std::collections::BTreeMap::new();
             for version in &stable_versions {
-                let version_clean = version.strip_prefix('v').unwrap_or(&version);
+                let version_clean = version.strip_prefix('v').unwrap_or(version);
                 let parts: Vec<&str> = version_clean.split('.').collect();
                 if parts.len() >= 2 {
                     let major_minor = format!("{}.{}", parts[0], parts[1]);
                     let clean_version = version.strip_prefix('v').unwrap_or(version).to_string();
                     major_minor_groups
                         .entry(major_minor)
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(clean_version);
                 }
             }
@@ -347,7 +463,7 @@ impl Runtime for GoRuntime {
             sorted_groups.sort_by(|a, b| {
                 let parse_major_minor = |s: &str| -> (u32, u32) {
                     let parts: Vec<&str> = s.split('.').collect();
-                    let major = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
+                    let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
                     let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
                     (major, minor)
                 };
@@ -357,24 +473,12 @@ impl Runtime for GoRuntime {
             });
 
             // Display top 4 major.minor groups
-            let mut count = 0;
-            for (major_minor, mut versions) in sorted_groups {
-                if count >= 4 {
-                    break;
-                }
-
+            for (major_minor, mut versions) in sorted_groups.into_iter().take(4) {
                 // Sort versions within the group (newest first)
-                versions.sort_by(|a, b| {
-                    let parse_patch = |v: &str| -> u32 {
-                        let parts: Vec<&str> = v.split('.').collect();
-                        parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0)
-                    };
-                    parse_patch(b).cmp(&parse_patch(a))
-                });
+                versions.sort_by(|a, b| compare_versions(b, a));
 
                 let versions_str = versions.join(", ");
                 result.push(format!("{}: {}", major_minor, versions_str));
-                count += 1;
             }
 
             // Add truncation message for stable versions
@@ -467,7 +571,7 @@ impl Runtime for GoRuntime {
                                 let major_minor = format!("{}.{}", base_parts[0], base_parts[1]);
                                 rc_groups
                                     .entry(major_minor)
-                                    .or_insert_with(Vec::new)
+                                    .or_default()
                                     .push(clean_version);
                             }
                         }
@@ -479,7 +583,7 @@ impl Runtime for GoRuntime {
                                 let major_minor = format!("{}.{}", base_parts[0], base_parts[1]);
                                 beta_groups
                                     .entry(major_minor)
-                                    .or_insert_with(Vec::new)
+                                    .or_default()
                                     .push(clean_version);
                             }
                         }
@@ -548,13 +652,12 @@ impl Runtime for GoRuntime {
         let mut all_versions = Vec::new();
 
         for element in document.select(&div_selector) {
-            if let Some(id) = element.value().attr("id") {
-                if id.starts_with("go")
-                    && id.len() > 2
-                    && id.chars().nth(2).map_or(false, |c| c.is_ascii_digit())
-                {
-                    all_versions.push(id.to_string());
-                }
+            if let Some(id) = element.value().attr("id")
+                && id.starts_with("go")
+                && id.len() > 2
+                && id.chars().nth(2).is_some_and(|c| c.is_ascii_digit())
+            {
+                all_versions.push(id.to_string());
             }
         }
 
@@ -577,30 +680,7 @@ impl Runtime for GoRuntime {
 
         // Sort each category by semantic version (newest first)
         let sort_versions = |versions: &mut Vec<String>| {
-            versions.sort_by(|a, b| {
-                let parse_version = |v: &str| -> (u32, u32, u32, u32) {
-                    let without_go = v.strip_prefix("go").unwrap_or(v);
-                    let (base_version, rc_beta_num) = if let Some(rc_pos) = without_go.find("rc") {
-                        let num = without_go[rc_pos + 2..].parse().unwrap_or(0);
-                        (&without_go[..rc_pos], num)
-                    } else if let Some(beta_pos) = without_go.find("beta") {
-                        let num = without_go[beta_pos + 4..].parse().unwrap_or(0);
-                        (&without_go[..beta_pos], num)
-                    } else {
-                        (without_go, 0)
-                    };
-
-                    let parts: Vec<&str> = base_version.split('.').collect();
-                    let major = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    let patch = parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0);
-                    (major, minor, patch, rc_beta_num)
-                };
-
-                let version_a = parse_version(a);
-                let version_b = parse_version(b);
-                version_b.cmp(&version_a) // Reverse order for newest first
-            });
+            versions.sort_by(|a, b| compare_versions(b, a));
         };
 
         display_step("Sorting versions by release type");
@@ -643,6 +723,9 @@ impl Runtime for GoRuntime {
         let json_data = self.fetch_available_versions()?;
         let available_versions = Self::parse_api_response(&json_data)?;
 
+        // Accept the v1.x.y storage naming as well as go1.x.y release names
+        let version_input = version_input.strip_prefix('v').unwrap_or(version_input);
+
         match version_input {
             "latest" => {
                 // Get the latest stable version (first stable in the list)
@@ -652,37 +735,48 @@ impl Runtime for GoRuntime {
                     .map(|r| r.version.clone())
                     .ok_or_else(|| RvmError::VersionNotFound("latest".to_string()))
             }
-            v if v.chars().all(|c| c.is_ascii_digit()) => {
-                // Major version only (e.g., "1")
-                let target_major = format!("go{}", v);
-                available_versions
-                    .iter()
-                    .find(|r| r.version.starts_with(&target_major) && r.stable)
-                    .map(|r| r.version.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
-            }
-            v if v.matches('.').count() == 1 => {
-                // Major.minor version (e.g., "1.21")
-                let target_prefix = format!("go{}", v);
-                available_versions
-                    .iter()
-                    .find(|r| r.version.starts_with(&target_prefix) && r.stable)
-                    .map(|r| r.version.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
-            }
             v => {
-                // Specific version (e.g., "1.21.5" or "go1.21.5")
-                let target_version = if v.starts_with("go") {
-                    v.to_string()
-                } else {
-                    format!("go{}", v)
-                };
-                available_versions
-                    .iter()
-                    .find(|r| r.version == target_version)
-                    .map(|r| r.version.clone())
+                // Exact release, partial version or range (e.g., "1.21.5", "go1.22rc1", "1.21",
+                // ">=1.21 <1.23"), newest matching release wins. Partial versions and ranges
+                // only pick release candidates and betas when they name one
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                select_newest(available_versions.iter().map(|r| r.version.as_str()), v)
+                    .map(|version| version.to_string())
                     .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
             }
         }
     }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // A go.work anywhere above takes precedence over the nearest go.mod, as with the go command
+        let project_file = ["go.work", "go.mod"].into_iter().find_map(|file_name| {
+            start
+                .ancestors()
+                .map(|dir| dir.join(file_name))
+                .find(|path| path.is_file())
+        });
+
+        let Some(path) = project_file else {
+            return Ok(None);
+        };
+
+        let (go_directive, toolchain_directive) = Self::read_go_directives(&path)?;
+        // A toolchain older than the go directive can't build the module, the go command then
+        // switches to a newer release as well
+        let release = match (toolchain_directive, go_directive) {
+            (Some(toolchain), Some(go_version))
+                if Version::parse(&toolchain) < Version::parse(&go_version) =>
+            {
+                self.minimum_release_for(&go_version)?
+            }
+            (Some(toolchain), _) => toolchain,
+            (None, Some(go_version)) => self.minimum_release_for(&go_version)?,
+            (None, None) => return Ok(None),
+        };
+
+        Ok(Some(VersionHint {
+            version: Self::storage_version(&release),
+            source: path,
+        }))
+    }
 }
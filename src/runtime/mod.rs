@@ -1,9 +1,17 @@
+use crate::utils::download::Platform;
 use crate::utils::error::{Result, RvmError};
+use crate::utils::project::VersionHint;
 use std::fs;
+use std::path::Path;
 
 // Import runtime modules
 pub mod node;
+pub mod deno;
+pub mod bun;
 pub mod go;
+pub mod python;
+pub mod pypy;
+pub mod rustup;
 pub mod tailwindcss;
 
 /// Runtime trait that all runtime managers must implement
@@ -14,6 +22,20 @@ pub trait Runtime: Send {
     fn update(&self) -> Result<()>;
     fn prune(&self, keep_version: &str) -> Result<()>;
     
+    // Lockfile Functions
+    /// Whether downloads can be locked, runtimes whose installer verifies its own downloads
+    /// only have their version locked
+    fn lockable(&self) -> bool {
+        true
+    }
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String>;
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>>;
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()>;
+    
     // Version Management Functions
     fn set_default(&self, version: &str) -> Result<()>;
     fn use_version(&self, version: &str) -> Result<()>;
@@ -21,7 +43,9 @@ pub trait Runtime: Send {
     // Information & Discovery Functions
     fn list_installed(&self) -> Result<Vec<String>>;
     fn list_available(&self) -> Result<Vec<String>>;
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>>;
     fn resolve_version(&self, version_input: &str) -> Result<String>;
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>>;
     
     // Internal version resolution helpers
     fn fetch_available_versions(&self) -> Result<serde_json::Value>;
@@ -38,13 +62,14 @@ pub fn list_supported_runtimes() -> Result<Vec<String>> {
             let entry = entry?;
             let path = entry.path();
             
-            if path.is_file() && path.extension().map_or(false, |ext| ext == "rs") {
-                if let Some(file_name) = path.file_stem() {
-                    let name = file_name.to_string_lossy();
-                    // Skip mod.rs
-                    if name != "mod" {
-                        runtimes.push(name.to_string());
-                    }
+            if path.is_file()
+                && path.extension().is_some_and(|ext| ext == "rs")
+                && let Some(file_name) = path.file_stem()
+            {
+                let name = file_name.to_string_lossy();
+                // Skip mod.rs
+                if name != "mod" {
+                    runtimes.push(name.to_string());
                 }
             }
         }
@@ -54,12 +79,22 @@ pub fn list_supported_runtimes() -> Result<Vec<String>> {
     Ok(runtimes)
 }
 
+/// Runtimes that get_runtime can create with versions under ~/.<runtime>/<version>. rustup and
+/// rust are left out, rustup's proxies follow rust-toolchain.toml themselves
+pub const RUNTIMES: [&str; 7] = ["node", "deno", "bun", "python", "pypy", "go", "tailwindcss"];
+
 /// Create a runtime instance by name
 pub fn get_runtime(name: &str) -> Result<Box<dyn Runtime>> {
     match name {
         "node" => Ok(Box::new(node::NodeRuntime)),
+        "deno" => Ok(Box::new(deno::DenoRuntime)),
+        "bun" => Ok(Box::new(bun::BunRuntime)),
+        "python" => Ok(Box::new(python::CPythonRuntime)),
+        "pypy" => Ok(Box::new(pypy::PyPyRuntime)),
         "go" => Ok(Box::new(go::GoRuntime)),
         "tailwindcss" => Ok(Box::new(tailwindcss::TailwindCssRuntime)),
+        "rustup" => Ok(Box::new(rustup::RustupRuntime)),
+        "rust" => Ok(Box::new(rustup::RustRuntime)),
         _ => Err(RvmError::UnsupportedRuntime(name.to_string())),
     }
 }
\ No newline at end of file
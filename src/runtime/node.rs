@@ -1,9 +1,12 @@
 use super::Runtime;
-use crate::utils::download::{download_and_extract, get_architecture};
+use crate::utils::download::{Platform, download_and_extract, find_checksum};
 use crate::utils::error::{Result, RvmError};
 use crate::utils::profile::{reload_profile, set_default_runtime, remove_runtime_from_path};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{VersionReq, select_newest};
 use crate::utils::ui::{display_step, display_success, display_error};
-use crate::utils::version::{is_version_installed, get_runtime_home, remove_version, list_installed_versions, resolve_installed_version, apply_version_to_current_session};
+use crate::utils::version::{find_installed_version, is_version_installed, get_runtime_home, remove_version, list_installed_versions, resolve_installed_version, apply_version_to_current_session, compare_versions};
+use std::path::Path;
 use reqwest::blocking;
 use serde::Deserialize;
 
@@ -22,38 +25,115 @@ impl NodeRuntime {
         let releases: Vec<NodeRelease> = serde_json::from_value(json_data.clone())?;
         Ok(releases)
     }
-    
-}
 
-impl Runtime for NodeRuntime {
-    fn add(&self, version: Option<&str>) -> Result<()> {
-        let version_str = version.unwrap_or("latest");
-        display_step(&format!("Adding Node.js version: {}", version_str));
+    /// Whether the input is an nvm-style LTS alias ("lts", "lts/*" or "lts/<codename>")
+    fn is_lts_alias(version_input: &str) -> bool {
+        version_input == "lts" || version_input.starts_with("lts/")
+    }
 
-        // Step 1: Parse and resolve version
-        display_step("Resolving version from Node.js API");
-        let resolved_version = self.resolve_version(version_str)?;
-        display_success(&format!("Resolved to version: {}", resolved_version));
+    /// Whether the input is an alias rather than a version or range ("node", "latest" or LTS)
+    fn is_alias(version_input: &str) -> bool {
+        version_input == "node" || version_input == "latest" || Self::is_lts_alias(version_input)
+    }
 
+    /// Whether a release with this LTS codename belongs to an LTS alias, codenames are matched
+    /// case-insensitively
+    fn matches_lts_alias(codename: Option<&str>, alias: &str) -> bool {
+        match alias.strip_prefix("lts/") {
+            None | Some("*") => codename.is_some(),
+            Some(wanted) => codename.is_some_and(|c| c.eq_ignore_ascii_case(wanted)),
+        }
+    }
+
+    /// LTS codename from a release's node_version.h, None for Current releases
+    fn parse_lts_codename(header: &str) -> Option<String> {
+        let define = |name: &str| {
+            header.lines().find_map(|line| {
+                let rest = line.trim().strip_prefix("#define ")?.trim_start().strip_prefix(name)?;
+                rest.starts_with(char::is_whitespace).then(|| rest.trim().to_string())
+            })
+        };
+
+        if define("NODE_VERSION_IS_LTS").as_deref() != Some("1") {
+            return None;
+        }
+        define("NODE_VERSION_LTS_CODENAME")
+            .map(|codename| codename.trim_matches('"').to_string())
+            .filter(|codename| !codename.is_empty())
+    }
+
+    /// LTS codename of an installed release, read from its headers so no network is needed
+    fn installed_lts_codename(version: &str) -> Result<Option<String>> {
+        let header = get_runtime_home("node")?
+            .join(version)
+            .join("include/node/node_version.h");
+        match std::fs::read_to_string(header) {
+            Ok(content) => Ok(Self::parse_lts_codename(&content)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Newest installed release matching "node", "latest" or an LTS alias, given each
+    /// installed version with its LTS codename
+    fn newest_for_alias(installed: &[(String, Option<String>)], alias: &str) -> Option<String> {
+        installed
+            .iter()
+            .filter(|(_, codename)| {
+                !Self::is_lts_alias(alias) || Self::matches_lts_alias(codename.as_deref(), alias)
+            })
+            .map(|(version, _)| version)
+            .max_by(|a, b| compare_versions(a, b))
+            .cloned()
+    }
+
+    /// Resolve version input against installed versions, including LTS aliases and semver
+    /// ranges (e.g., "lts/iron" -> "v20.18.0", ">=18 <21" -> "v20.18.0")
+    fn resolve_installed(&self, version_input: &str) -> Result<String> {
+        if Self::is_alias(version_input) {
+            return self
+                .find_installed(version_input)?
+                .ok_or_else(|| RvmError::VersionNotFound(version_input.to_string()));
+        }
+
+        // Exact versions, partial versions and ranges resolve the same way for every runtime
+        resolve_installed_version("node", version_input)
+    }
+
+    /// Read the Node.js version from package.json, preferring volta.node over engines.node
+    fn package_json_version(path: &Path) -> Result<Option<String>> {
+        let content = std::fs::read_to_string(path)?;
+        let package: serde_json::Value = serde_json::from_str(&content)?;
+
+        let version = package
+            .pointer("/volta/node")
+            .or_else(|| package.pointer("/engines/node"))
+            .and_then(|v| v.as_str())
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty());
+        Ok(version)
+    }
+
+    /// Download, extract and activate an exact Node.js version, optionally verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
         // Step 2: Check if already installed (has built-in messaging)
-        if is_version_installed("node", &resolved_version)? {
-            return Err(RvmError::VersionAlreadyInstalled(resolved_version));
+        if is_version_installed("node", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
         }
 
         // Step 3: Download and extract (has built-in messaging)
-        let arch = get_architecture()?;
-        let filename = format!("node-{}-linux-{}.tar.xz", resolved_version, arch);
-        let download_url = format!("https://nodejs.org/dist/{}/{}", resolved_version, filename);
+        let download_url = self.artifact_url(resolved_version, &Platform::current()?)?;
 
         download_and_extract(
             &download_url,
             "node",
-            &resolved_version,
+            resolved_version,
             &["bin/node", "bin/npm"], // Make node and npm executable
+            sha256,
         )?;
 
         // Step 4: Set as default (has built-in messaging)
-        set_default_runtime("node", &resolved_version)?;
+        set_default_runtime("node", resolved_version)?;
 
         // Step 5: Automatically reload profile (has built-in messaging)
         reload_profile()?;
@@ -61,6 +141,50 @@ impl Runtime for NodeRuntime {
         display_success(&format!("Node.js {} installation completed successfully!", resolved_version));
         Ok(())
     }
+}
+
+impl Runtime for NodeRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding Node.js version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from Node.js API");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked Node.js version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let filename = format!("node-{}-linux-{}.tar.xz", resolved_version, platform.arch);
+        Ok(format!("https://nodejs.org/dist/{}/{}", resolved_version, filename))
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        let sums_url = format!("https://nodejs.org/dist/{}/SHASUMS256.txt", resolved_version);
+        display_step(&format!("Fetching the checksums of Node.js {}", resolved_version));
+        let response = blocking::get(&sums_url)?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch Node.js checksums: {}",
+                response.status()
+            )));
+        }
+
+        let url = self.artifact_url(resolved_version, platform)?;
+        let filename = url.rsplit('/').next().unwrap_or_default();
+        Ok(find_checksum(&response.text()?, filename))
+    }
 
     fn remove(&self, version: Option<&str>) -> Result<()> {
         match version {
@@ -106,17 +230,14 @@ impl Runtime for NodeRuntime {
                 
                 // Clean up the entire node directory if it's empty
                 let runtime_home = get_runtime_home("node")?;
-                if runtime_home.exists() {
-                    match std::fs::read_dir(&runtime_home) {
-                        Ok(mut entries) => {
-                            if entries.next().is_none() {
-                                display_step("Cleaning up empty Node.js directory");
-                                std::fs::remove_dir(&runtime_home)?;
-                                display_success("Removed empty Node.js directory");
-                            }
-                        }
-                        Err(_) => {} // Directory doesn't exist or can't read, skip cleanup
-                    }
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty Node.js directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty Node.js directory");
                 }
                 
                 // Reload profile to apply changes
@@ -209,8 +330,8 @@ impl Runtime for NodeRuntime {
     fn set_default(&self, version: &str) -> Result<()> {
         display_step(&format!("Setting Node.js {} as default", version));
         
-        // Resolve version first (e.g., "18" -> "v18.20.0")
-        let resolved_version = resolve_installed_version("node", version)?;
+        // Resolve version first (e.g., "18" -> "v18.20.0", "lts/iron" -> "v20.18.0")
+        let resolved_version = self.resolve_installed(version)?;
         display_success(&format!("Resolved to installed version: {}", resolved_version));
         
         // Set as default (has built-in messaging)
@@ -230,8 +351,8 @@ impl Runtime for NodeRuntime {
     fn use_version(&self, version: &str) -> Result<()> {
         display_step(&format!("Switching to Node.js {} for current session", version));
         
-        // Resolve version first (e.g., "18" -> "v18.20.0")
-        let resolved_version = resolve_installed_version("node", version)?;
+        // Resolve version first (e.g., "18" -> "v18.20.0", "lts/iron" -> "v20.18.0")
+        let resolved_version = self.resolve_installed(version)?;
         display_success(&format!("Resolved to installed version: {}", resolved_version));
         
         // Apply version to current session
@@ -248,6 +369,21 @@ impl Runtime for NodeRuntime {
         list_installed_versions("node")
     }
 
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        if !Self::is_alias(version_input) {
+            return find_installed_version("node", version_input);
+        }
+
+        let installed = list_installed_versions("node")?
+            .into_iter()
+            .map(|version| {
+                let codename = Self::installed_lts_codename(&version)?;
+                Ok((version, codename))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::newest_for_alias(&installed, version_input))
+    }
+
     fn list_available(&self) -> Result<Vec<String>> {
         use crate::utils::version::{VersionInfo, detect_channel, extract_major_minor, group_versions_by_channel};
         
@@ -286,56 +422,90 @@ impl Runtime for NodeRuntime {
         let available_versions = Self::parse_api_response(&json_data)?;
 
         match version_input {
-            "latest" => {
+            "latest" | "node" => {
                 // Get the latest stable version (first in the list)
                 available_versions
                     .first()
                     .map(|r| r.version.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound("latest".to_string()))
+                    .ok_or_else(|| RvmError::VersionNotFound(version_input.to_string()))
             }
-            "lts" => {
-                // Get the latest LTS version
+            v if Self::is_lts_alias(v) => {
+                // Get the latest LTS version, optionally of a codename (e.g., "lts/iron")
                 available_versions
                     .iter()
-                    .find(|r| !r.lts.is_boolean() || r.lts.as_bool() != Some(false))
-                    .map(|r| r.version.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound("lts".to_string()))
-            }
-            v if v.chars().all(|c| c.is_ascii_digit()) => {
-                // Major version only (e.g., "20")
-                let target_major = format!("v{}", v);
-                available_versions
-                    .iter()
-                    .find(|r| r.version.starts_with(&target_major))
-                    .map(|r| r.version.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
-            }
-            v if v.matches('.').count() == 1 => {
-                // Major.minor version (e.g., "20.11")
-                let target_prefix = if v.starts_with('v') {
-                    v.to_string()
-                } else {
-                    format!("v{}", v)
-                };
-                available_versions
-                    .iter()
-                    .find(|r| r.version.starts_with(&target_prefix))
+                    .find(|r| Self::matches_lts_alias(r.lts.as_str(), v))
                     .map(|r| r.version.clone())
                     .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
             }
             v => {
-                // Specific version (e.g., "20.11.0" or "20.11.0-beta.1")
-                let target_version = if v.starts_with('v') {
-                    v.to_string()
-                } else {
-                    format!("v{}", v)
-                };
-                available_versions
-                    .iter()
-                    .find(|r| r.version == target_version)
-                    .map(|r| r.version.clone())
+                // Exact version, partial version or semver range (e.g., "20.11.0", "20", "^20.1",
+                // ">=18 <21"), newest matching release wins
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                select_newest(available_versions.iter().map(|r| r.version.as_str()), v)
+                    .map(|version| version.to_string())
                     .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
             }
         }
     }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest directory wins, .nvmrc > .node-version > package.json
+        for dir in start.ancestors() {
+            for file_name in [".nvmrc", ".node-version"] {
+                let path = dir.join(file_name);
+                if path.is_file()
+                    && let Some(version) = read_version_file(&path)?
+                {
+                    return Ok(Some(VersionHint { version, source: path }));
+                }
+            }
+
+            let package_json = dir.join("package.json");
+            if package_json.is_file()
+                && let Some(version) = Self::package_json_version(&package_json)?
+            {
+                return Ok(Some(VersionHint { version, source: package_json }));
+            }
+        }
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(releases: &[(&str, Option<&str>)]) -> Vec<(String, Option<String>)> {
+        releases
+            .iter()
+            .map(|(version, codename)| (version.to_string(), codename.map(str::to_string)))
+            .collect()
+    }
+
+    #[test]
+    fn lts_codename_is_read_from_the_version_header() {
+        let lts = "#define NODE_VERSION_IS_LTS 1\n#define NODE_VERSION_LTS_CODENAME \"Iron\"\n";
+        assert_eq!(NodeRuntime::parse_lts_codename(lts).as_deref(), Some("Iron"));
+
+        let current = "#define NODE_VERSION_IS_LTS 0\n#define NODE_VERSION_LTS_CODENAME \"\"\n";
+        assert_eq!(NodeRuntime::parse_lts_codename(current), None);
+    }
+
+    #[test]
+    fn lts_codename_pin_matches_installed_release() {
+        let installed = installed(&[
+            ("v18.19.0", Some("Hydrogen")),
+            ("v20.11.0", Some("Iron")),
+            ("v20.9.0", Some("Iron")),
+            ("v21.6.0", None),
+        ]);
+
+        let newest = |alias| NodeRuntime::newest_for_alias(&installed, alias);
+        assert_eq!(newest("lts/iron").as_deref(), Some("v20.11.0"));
+        assert_eq!(newest("lts/hydrogen").as_deref(), Some("v18.19.0"));
+        assert_eq!(newest("lts/*").as_deref(), Some("v20.11.0"));
+        assert_eq!(newest("lts").as_deref(), Some("v20.11.0"));
+        assert_eq!(newest("node").as_deref(), Some("v21.6.0"));
+        assert_eq!(newest("lts/jod"), None);
+    }
 }
@@ -0,0 +1,497 @@
+use super::Runtime;
+use crate::utils::download::{Platform, download_and_extract};
+use crate::utils::error::{Result, RvmError};
+use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{Version, VersionReq};
+use crate::utils::ui::{display_error, display_step, display_success};
+use crate::utils::version::{
+    apply_version_to_current_session, get_runtime_home, is_version_installed,
+    list_installed_versions, remove_version,
+};
+use reqwest::blocking;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// PyPy runtime manager
+pub struct PyPyRuntime;
+
+/// Releases shown per Python level by `rvm list available pypy`
+const MAX_LISTED: usize = 4;
+
+/// PyPy versions start at 7, Python levels are 2 or 3, so a bare "7.3" is a PyPy version
+const FIRST_PYPY_MAJOR: u64 = 7;
+
+#[derive(Deserialize)]
+struct PyPyRelease {
+    pypy_version: String,
+    python_version: String,
+    stable: bool,
+}
+
+impl PyPyRelease {
+    /// Version name covering both the Python level and the PyPy version, as used by the
+    /// download files (e.g. "pypy3.10-v7.3.17")
+    fn name(&self) -> Option<String> {
+        let python = Version::parse(&self.python_version)?;
+        Some(format!("pypy{}.{}-v{}", python.major, python.minor, self.pypy_version))
+    }
+}
+
+impl PyPyRuntime {
+    /// Parse the versions.json index
+    fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<PyPyRelease>> {
+        let releases: Vec<PyPyRelease> = serde_json::from_value(json_data.clone())?;
+        Ok(releases)
+    }
+
+    /// Split a version name into its Python level and PyPy version
+    /// (e.g. "pypy3.10-v7.3.17" -> (3.10, 7.3.17))
+    fn parse_name(name: &str) -> Option<(Version, Version)> {
+        let (python, pypy) = name.strip_prefix("pypy")?.split_once('-')?;
+        Some((Version::parse(python)?, Version::parse(pypy)?))
+    }
+
+    /// Split version input into Python level and PyPy version requirements. Accepts names
+    /// ("pypy3.10-v7.3.17"), pyenv names ("pypy3.10-7.3.17", "pypy3.10"), Python levels ("3.10",
+    /// "^3.9") and PyPy versions ("7.3.17"). None means any
+    fn parse_input(version_input: &str) -> (Option<&str>, Option<&str>) {
+        if let Some(rest) = version_input.strip_prefix("pypy") {
+            return match rest.split_once('-') {
+                Some((python, pypy)) => (Some(python), Some(pypy)),
+                None if rest.is_empty() => (None, None),
+                None => (Some(rest), None),
+            };
+        }
+
+        let major = version_input
+            .trim_start_matches(|c: char| !c.is_ascii_digit())
+            .split(|c: char| !c.is_ascii_digit())
+            .next()
+            .and_then(|major| major.parse::<u64>().ok());
+        match major {
+            Some(major) if major >= FIRST_PYPY_MAJOR => (None, Some(version_input)),
+            _ => (Some(version_input), None),
+        }
+    }
+
+    /// Pick the newest version name matching the input, preferring newer PyPy releases and then
+    /// newer Python levels (e.g. "7.3.17" picks "pypy3.10-v7.3.17" over "pypy3.9-v7.3.17")
+    fn select_newest<'a, I>(names: I, version_input: &str) -> Result<Option<&'a str>>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let (python_input, pypy_input) = Self::parse_input(version_input);
+        let parse_req = |input: &str| {
+            VersionReq::parse(input)
+                .ok_or_else(|| RvmError::InvalidVersionFormat(version_input.to_string()))
+        };
+        let python_req = python_input.map(parse_req).transpose()?;
+        let pypy_req = pypy_input.map(parse_req).transpose()?;
+
+        Ok(names
+            .into_iter()
+            .filter_map(|name| Self::parse_name(name).map(|(python, pypy)| (pypy, python, name)))
+            .filter(|(pypy, python, _)| {
+                python_req.as_ref().is_none_or(|req| req.matches(python))
+                    && pypy_req.as_ref().is_none_or(|req| req.matches(pypy))
+            })
+            .max_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)))
+            .map(|(_, _, name)| name))
+    }
+
+    /// Resolve version input against installed versions (e.g. "3.10" -> "pypy3.10-v7.3.17")
+    fn resolve_installed(&self, version_input: &str) -> Result<String> {
+        let installed_versions = list_installed_versions("pypy")?;
+
+        if installed_versions.is_empty() {
+            return Err(RvmError::VersionNotFound("No pypy versions installed".to_string()));
+        }
+
+        if let Some(installed) = self.find_installed(version_input)? {
+            return Ok(installed);
+        }
+
+        // If no match found, show available versions
+        display_error(&format!("pypy {} is not installed", version_input));
+        println!("Available installed versions:");
+        for version in &installed_versions {
+            println!("  ✓ {}", version);
+        }
+
+        Err(RvmError::VersionNotFound(version_input.to_string()))
+    }
+
+    /// Download, extract and activate an exact PyPy version, optionally verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
+        // Step 2: Check if already installed (has built-in messaging)
+        if is_version_installed("pypy", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
+        }
+
+        // Step 3: Download and extract (has built-in messaging)
+        let download_url = self.artifact_url(resolved_version, &Platform::current()?)?;
+
+        download_and_extract(&download_url, "pypy", resolved_version, &["bin/pypy3"], sha256)?;
+
+        // Make sure python runs PyPy when this version is on PATH
+        let bin_dir = get_runtime_home("pypy")?.join(resolved_version).join("bin");
+        if !bin_dir.join("python").exists() {
+            std::os::unix::fs::symlink("pypy3", bin_dir.join("python"))?;
+        }
+
+        // Step 4: Set as default (has built-in messaging)
+        set_default_runtime("pypy", resolved_version)?;
+
+        // Step 5: Automatically reload profile (has built-in messaging)
+        reload_profile()?;
+
+        display_success(&format!("PyPy {} installation completed successfully!", resolved_version));
+        Ok(())
+    }
+}
+
+impl Runtime for PyPyRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding PyPy version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from PyPy versions.json");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked PyPy version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let build = match platform.arch {
+            "x64" => "linux64",
+            "arm64" => "aarch64",
+            arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        };
+        Ok(format!(
+            "https://downloads.python.org/pypy/{}-{}.tar.bz2",
+            resolved_version, build
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        _resolved_version: &str,
+        _platform: &Platform,
+    ) -> Result<Option<String>> {
+        // PyPy lists its checksums on a web page only, rvm lock hashes the first download
+        Ok(None)
+    }
+
+    fn remove(&self, version: Option<&str>) -> Result<()> {
+        match version {
+            Some(v) => {
+                display_step(&format!("Removing PyPy version: {}", v));
+
+                // Check if version is installed
+                if !is_version_installed("pypy", v)? {
+                    display_error(&format!("PyPy {} is not installed", v));
+                    return Err(RvmError::VersionNotFound(v.to_string()));
+                }
+
+                // Remove from PATH in profile
+                remove_runtime_from_path("pypy", v)?;
+
+                // Remove from filesystem (has built-in messaging)
+                remove_version("pypy", v)?;
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("PyPy {} removed successfully", v));
+            }
+            None => {
+                display_step("Removing all PyPy versions");
+
+                // Get list of installed versions
+                let installed_versions = list_installed_versions("pypy")?;
+
+                if installed_versions.is_empty() {
+                    display_success("No PyPy versions are installed");
+                    return Ok(());
+                }
+
+                display_step(&format!("Found {} PyPy versions to remove", installed_versions.len()));
+
+                // Remove each version
+                for version in &installed_versions {
+                    display_step(&format!("Removing PyPy {}", version));
+                    remove_runtime_from_path("pypy", version)?;
+                    remove_version("pypy", version)?;
+                }
+
+                // Clean up the entire pypy directory if it's empty
+                let runtime_home = get_runtime_home("pypy")?;
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty PyPy directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty PyPy directory");
+                }
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Removed all {} PyPy versions successfully", installed_versions.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating PyPy to latest version");
+
+        // Get the latest version from versions.json
+        let latest_version = self.resolve_version("latest")?;
+        display_success(&format!("Latest PyPy version is: {}", latest_version));
+
+        // Check if it's already installed
+        if is_version_installed("pypy", &latest_version)? {
+            display_success(&format!("PyPy {} is already installed", latest_version));
+
+            // Set it as default if it's not already
+            display_step("Ensuring latest version is set as default");
+            set_default_runtime("pypy", &latest_version)?;
+            reload_profile()?;
+
+            display_success("PyPy is already up to date");
+            return Ok(());
+        }
+
+        // Install the latest version
+        display_step(&format!("Installing PyPy {}", latest_version));
+        self.install_resolved(&latest_version, None)?;
+
+        display_success(&format!("Successfully updated PyPy to {}", latest_version));
+        Ok(())
+    }
+
+    fn prune(&self, keep_version: &str) -> Result<()> {
+        display_step(&format!("Pruning PyPy versions (keeping {})", keep_version));
+
+        // Resolve the version to keep (e.g., "3.10" -> "pypy3.10-v7.3.17")
+        let resolved_keep_version = self.resolve_installed(keep_version)?;
+        display_success(&format!("Will keep PyPy version: {}", resolved_keep_version));
+
+        // Get list of all installed versions
+        let installed_versions = list_installed_versions("pypy")?;
+
+        if installed_versions.is_empty() {
+            display_success("No PyPy versions are installed");
+            return Ok(());
+        }
+
+        // Filter out the version to keep
+        let versions_to_remove: Vec<String> = installed_versions
+            .into_iter()
+            .filter(|v| *v != resolved_keep_version)
+            .collect();
+
+        if versions_to_remove.is_empty() {
+            display_success(&format!("Only {} is installed, nothing to prune", resolved_keep_version));
+            return Ok(());
+        }
+
+        display_step(&format!("Found {} PyPy versions to remove", versions_to_remove.len()));
+
+        // Remove each version except the one to keep
+        for version in &versions_to_remove {
+            display_step(&format!("Removing PyPy {}", version));
+
+            // Remove from PATH in profile
+            remove_runtime_from_path("pypy", version)?;
+
+            // Remove from filesystem
+            remove_version("pypy", version)?;
+        }
+
+        // Ensure the version to keep is set as default
+        display_step(&format!("Setting {} as default", resolved_keep_version));
+        set_default_runtime("pypy", &resolved_keep_version)?;
+
+        // Reload profile to apply changes
+        reload_profile()?;
+
+        display_success(&format!("Pruned {} PyPy versions, kept {}", versions_to_remove.len(), resolved_keep_version));
+        Ok(())
+    }
+
+    fn set_default(&self, version: &str) -> Result<()> {
+        display_step(&format!("Setting PyPy {} as default", version));
+
+        // Resolve version first (e.g., "3.10" -> "pypy3.10-v7.3.17")
+        let resolved_version = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Set as default (has built-in messaging)
+        set_default_runtime("pypy", &resolved_version)?;
+
+        // Reload profile (has built-in messaging)
+        reload_profile()?;
+
+        // Force reload current environment
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("pypy", &resolved_version, "pypy3")?;
+
+        display_success(&format!("PyPy {} is now the default version", resolved_version));
+        Ok(())
+    }
+
+    fn use_version(&self, version: &str) -> Result<()> {
+        display_step(&format!("Switching to PyPy {} for current session", version));
+
+        // Resolve version first (e.g., "3.10" -> "pypy3.10-v7.3.17")
+        let resolved_version = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Apply version to current session
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("pypy", &resolved_version, "pypy3")?;
+
+        display_success(&format!("PyPy {} is now active in current session", resolved_version));
+        println!("💡 This change is temporary. To make it permanent, run:");
+        println!("   rvm set pypy {}", version);
+        Ok(())
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        let mut installed_versions = list_installed_versions("pypy")?;
+        // Newest PyPy first, then newest Python level
+        installed_versions.sort_by_cached_key(|name| {
+            std::cmp::Reverse(Self::parse_name(name).map(|(python, pypy)| (pypy, python)))
+        });
+        Ok(installed_versions)
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        let installed_versions = list_installed_versions("pypy")?;
+        if installed_versions.iter().any(|v| v == version_input) {
+            return Ok(Some(version_input.to_string()));
+        }
+
+        let input = if version_input == "latest" { "pypy" } else { version_input };
+        let installed = Self::select_newest(installed_versions.iter().map(String::as_str), input)?;
+        Ok(installed.map(|installed| installed.to_string()))
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        display_step("Organizing versions for display");
+
+        // Group releases by channel and Python level, newest Python level first
+        let mut channels: BTreeMap<bool, BTreeMap<Version, Vec<(Version, String)>>> =
+            BTreeMap::new();
+        for release in &releases {
+            let (Some(name), Some(python), Some(pypy)) = (
+                release.name(),
+                Version::parse(&release.python_version),
+                Version::parse(&release.pypy_version),
+            ) else {
+                continue;
+            };
+            let level = Version::new(python.major, python.minor, 0);
+            channels
+                .entry(release.stable)
+                .or_default()
+                .entry(level)
+                .or_default()
+                .push((pypy, name));
+        }
+
+        let mut result = Vec::new();
+        for (stable, title) in [(true, "Stable"), (false, "Pre-release")] {
+            let Some(levels) = channels.remove(&stable) else {
+                continue;
+            };
+
+            result.push(format!("=== {} Versions ===", title));
+            let mut truncated = false;
+            for (level, mut names) in levels.into_iter().rev() {
+                names.sort_by(|a, b| b.0.cmp(&a.0));
+                names.dedup_by(|a, b| a.1 == b.1);
+                truncated |= names.len() > MAX_LISTED;
+                let names: Vec<String> =
+                    names.into_iter().take(MAX_LISTED).map(|(_, name)| name).collect();
+                result.push(format!("Python {}.{}: {}", level.major, level.minor, names.join(", ")));
+            }
+            if truncated {
+                result.push(
+                    "... displaying only latest versions, all other versions truncated".to_string(),
+                );
+            }
+            result.push(String::new());
+        }
+
+        // Remove trailing empty line
+        if result.last() == Some(&String::new()) {
+            result.pop();
+        }
+
+        display_success("Successfully retrieved and organized PyPy versions");
+        Ok(result)
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        let response = blocking::get("https://downloads.python.org/pypy/versions.json")?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch PyPy versions: {}",
+                response.status()
+            )));
+        }
+
+        let json_data: serde_json::Value = response.json()?;
+        Ok(json_data)
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        // An exact name may be a prerelease, anything else picks from the stable releases
+        let names: Vec<String> = releases
+            .iter()
+            .filter(|release| release.stable || release.name().as_deref() == Some(version_input))
+            .filter_map(PyPyRelease::name)
+            .collect();
+
+        let input = if version_input == "latest" { "pypy" } else { version_input };
+        Self::select_newest(names.iter().map(String::as_str), input)?
+            .map(|name| name.to_string())
+            .ok_or_else(|| RvmError::VersionNotFound(version_input.to_string()))
+    }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest .python-version wins, PyPy entries use pyenv names (e.g. "pypy3.10-7.3.17")
+        for dir in start.ancestors() {
+            let path = dir.join(".python-version");
+            if path.is_file()
+                && let Some(version) = read_version_file(&path)?
+            {
+                if !version.starts_with("pypy") {
+                    return Ok(None);
+                }
+                return Ok(Some(VersionHint { version, source: path }));
+            }
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,490 @@
+use super::Runtime;
+use crate::utils::download::{Platform, download_and_extract};
+use crate::utils::error::{Result, RvmError};
+use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{Identifier, Version, VersionReq, select_newest};
+use crate::utils::ui::{display_error, display_step, display_success};
+use crate::utils::version::{
+    VersionInfo, apply_version_to_current_session, compare_versions, extract_major_minor,
+    find_installed_version, get_runtime_home, group_versions_by_channel, is_version_installed,
+    list_installed_versions, remove_version, resolve_installed_version,
+};
+use reqwest::blocking;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// CPython runtime manager, installing the prebuilt python-build-standalone distributions
+pub struct CPythonRuntime;
+
+const RELEASES_URL: &str = "https://github.com/astral-sh/python-build-standalone/releases/download";
+
+/// How many of the newest python-build-standalone releases are searched for a version. Every
+/// release carries the newest patch of each supported minor version
+const MAX_RELEASES_SEARCHED: usize = 40;
+
+/// Releases shown by `rvm list available python`
+const RELEASES_LISTED: usize = 3;
+
+#[derive(Deserialize)]
+struct GitHubTag {
+    name: String,
+}
+
+/// A CPython build from one python-build-standalone release
+struct PythonBuild {
+    /// Installed version name, "<python version>+<release>" (e.g. "3.13.1+20241206")
+    version: String,
+    sha256: String,
+}
+
+impl CPythonRuntime {
+    /// Parse the GitHub tags API response into release tags, newest first. Releases are tagged
+    /// by date (e.g. "20241206")
+    fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<String>> {
+        let tags: Vec<GitHubTag> = serde_json::from_value(json_data.clone())?;
+        let mut releases: Vec<String> = tags
+            .into_iter()
+            .map(|tag| tag.name)
+            .filter(|name| name.len() == 8 && name.chars().all(|c| c.is_ascii_digit()))
+            .collect();
+        releases.sort_by(|a, b| b.cmp(a));
+        Ok(releases)
+    }
+
+    /// Rust target triple of the builds for a platform (e.g. "x86_64-unknown-linux-gnu")
+    fn target_triple(platform: &Platform) -> Result<String> {
+        let cpu = match platform.arch {
+            "x64" => "x86_64",
+            "arm64" => "aarch64",
+            arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+        };
+        let libc = if platform.musl { "musl" } else { "gnu" };
+        Ok(format!("{}-unknown-linux-{}", cpu, libc))
+    }
+
+    /// The install_only builds of a release for a platform, read from its SHA256SUMS
+    fn release_builds(release: &str, platform: &Platform) -> Result<Vec<PythonBuild>> {
+        let url = format!("{}/{}/SHA256SUMS", RELEASES_URL, release);
+        let response = blocking::get(&url)?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch the checksums of python-build-standalone {}: {}",
+                release,
+                response.status()
+            )));
+        }
+
+        let suffix = format!("-{}-install_only.tar.gz", Self::target_triple(platform)?);
+        let checksums = response.text()?;
+        Ok(checksums
+            .lines()
+            .filter_map(|line| {
+                let (sha256, file_name) = line.split_once(char::is_whitespace)?;
+                let version = file_name
+                    .trim()
+                    .strip_prefix("cpython-")?
+                    .strip_suffix(&suffix)?;
+                Some(PythonBuild {
+                    version: version.to_string(),
+                    sha256: sha256.to_string(),
+                })
+            })
+            .collect())
+    }
+
+    /// Find the newest build matching a version input, searching the newest releases first
+    fn find_build(&self, version_input: &str) -> Result<PythonBuild> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        // "latest" is the newest stable Python of the newest release
+        let version_req = match version_input {
+            "latest" => "*",
+            v => {
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                v
+            }
+        };
+
+        let platform = Platform::current()?;
+        for release in releases.iter().take(MAX_RELEASES_SEARCHED) {
+            display_step(&format!("Searching python-build-standalone {}", release));
+            // Older releases predate SHA256SUMS
+            let Ok(builds) = Self::release_builds(release, &platform) else {
+                continue;
+            };
+            let newest = select_newest(builds.iter().map(|b| b.version.as_str()), version_req)
+                .map(|version| version.to_string());
+            if let Some(newest) = newest
+                && let Some(build) = builds.into_iter().find(|b| b.version == newest)
+            {
+                return Ok(build);
+            }
+        }
+
+        Err(RvmError::VersionNotFound(version_input.to_string()))
+    }
+
+    /// Release channel of a Python version (e.g. "3.14.0a2" -> Alpha, "3.14.0rc1" -> RC)
+    fn channel(version: &str) -> String {
+        let pre = Version::parse(version).map(|v| v.pre).unwrap_or_default();
+        match pre.first() {
+            Some(Identifier::Alpha(tag)) if tag == "a" => "Alpha",
+            Some(Identifier::Alpha(tag)) if tag == "b" => "Beta",
+            Some(Identifier::Alpha(tag)) if tag == "rc" => "RC",
+            Some(_) => "Pre-release",
+            None => "Stable",
+        }
+        .to_string()
+    }
+
+    /// Download, extract and activate an exact CPython build, verifying its checksum against
+    /// the lockfile or the release's SHA256SUMS
+    fn install_resolved(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        // Step 2: Check if already installed (has built-in messaging)
+        if is_version_installed("python", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
+        }
+
+        // Step 3: Download and extract (has built-in messaging)
+        let download_url = self.artifact_url(resolved_version, &Platform::current()?)?;
+
+        download_and_extract(
+            &download_url,
+            "python",
+            resolved_version,
+            &["bin/python3", "bin/pip3"],
+            Some(sha256),
+        )?;
+
+        // The builds only ship python3 and pip3, add the unversioned names
+        let bin_dir = get_runtime_home("python")?.join(resolved_version).join("bin");
+        for (link, target) in [("python", "python3"), ("pip", "pip3")] {
+            if !bin_dir.join(link).exists() {
+                std::os::unix::fs::symlink(target, bin_dir.join(link))?;
+            }
+        }
+
+        // Step 4: Set as default (has built-in messaging)
+        set_default_runtime("python", resolved_version)?;
+
+        // Step 5: Automatically reload profile (has built-in messaging)
+        reload_profile()?;
+
+        display_success(&format!("Python {} installation completed successfully!", resolved_version));
+        Ok(())
+    }
+}
+
+impl Runtime for CPythonRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding Python version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from python-build-standalone releases");
+        let build = self.find_build(version_str)?;
+        display_success(&format!("Resolved to version: {}", build.version));
+
+        self.install_resolved(&build.version, &build.sha256)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked Python version: {}", resolved_version));
+        self.install_resolved(resolved_version, sha256)
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let (_, release) = resolved_version
+            .split_once('+')
+            .ok_or_else(|| RvmError::InvalidVersionFormat(resolved_version.to_string()))?;
+        Ok(format!(
+            "{}/{}/cpython-{}-{}-install_only.tar.gz",
+            RELEASES_URL,
+            release,
+            resolved_version.replace('+', "%2B"),
+            Self::target_triple(platform)?
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        let (_, release) = resolved_version
+            .split_once('+')
+            .ok_or_else(|| RvmError::InvalidVersionFormat(resolved_version.to_string()))?;
+        Ok(Self::release_builds(release, platform)?
+            .into_iter()
+            .find(|build| build.version == resolved_version)
+            .map(|build| build.sha256))
+    }
+
+    fn remove(&self, version: Option<&str>) -> Result<()> {
+        match version {
+            Some(v) => {
+                display_step(&format!("Removing Python version: {}", v));
+
+                // Check if version is installed
+                if !is_version_installed("python", v)? {
+                    display_error(&format!("Python {} is not installed", v));
+                    return Err(RvmError::VersionNotFound(v.to_string()));
+                }
+
+                // Remove from PATH in profile
+                remove_runtime_from_path("python", v)?;
+
+                // Remove from filesystem (has built-in messaging)
+                remove_version("python", v)?;
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Python {} removed successfully", v));
+            }
+            None => {
+                display_step("Removing all Python versions");
+
+                // Get list of installed versions
+                let installed_versions = list_installed_versions("python")?;
+
+                if installed_versions.is_empty() {
+                    display_success("No Python versions are installed");
+                    return Ok(());
+                }
+
+                display_step(&format!("Found {} Python versions to remove", installed_versions.len()));
+
+                // Remove each version
+                for version in &installed_versions {
+                    display_step(&format!("Removing Python {}", version));
+                    remove_runtime_from_path("python", version)?;
+                    remove_version("python", version)?;
+                }
+
+                // Clean up the entire python directory if it's empty
+                let runtime_home = get_runtime_home("python")?;
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty Python directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty Python directory");
+                }
+
+                // Reload profile to apply changes
+                reload_profile()?;
+
+                display_success(&format!("Removed all {} Python versions successfully", installed_versions.len()));
+            }
+        }
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating Python to latest version");
+
+        // Get the latest build from the newest release
+        let latest = self.find_build("latest")?;
+        display_success(&format!("Latest Python version is: {}", latest.version));
+
+        // Check if it's already installed
+        if is_version_installed("python", &latest.version)? {
+            display_success(&format!("Python {} is already installed", latest.version));
+
+            // Set it as default if it's not already
+            display_step("Ensuring latest version is set as default");
+            set_default_runtime("python", &latest.version)?;
+            reload_profile()?;
+
+            display_success("Python is already up to date");
+            return Ok(());
+        }
+
+        // Install the latest version
+        display_step(&format!("Installing Python {}", latest.version));
+        self.install_resolved(&latest.version, &latest.sha256)?;
+
+        display_success(&format!("Successfully updated Python to {}", latest.version));
+        Ok(())
+    }
+
+    fn prune(&self, keep_version: &str) -> Result<()> {
+        display_step(&format!("Pruning Python versions (keeping {})", keep_version));
+
+        // Resolve the version to keep (e.g., "3.12" -> "3.12.8+20241206")
+        let resolved_keep_version = resolve_installed_version("python", keep_version)?;
+        display_success(&format!("Will keep Python version: {}", resolved_keep_version));
+
+        // Get list of all installed versions
+        let installed_versions = list_installed_versions("python")?;
+
+        if installed_versions.is_empty() {
+            display_success("No Python versions are installed");
+            return Ok(());
+        }
+
+        // Filter out the version to keep
+        let versions_to_remove: Vec<String> = installed_versions
+            .into_iter()
+            .filter(|v| *v != resolved_keep_version)
+            .collect();
+
+        if versions_to_remove.is_empty() {
+            display_success(&format!("Only {} is installed, nothing to prune", resolved_keep_version));
+            return Ok(());
+        }
+
+        display_step(&format!("Found {} Python versions to remove", versions_to_remove.len()));
+
+        // Remove each version except the one to keep
+        for version in &versions_to_remove {
+            display_step(&format!("Removing Python {}", version));
+
+            // Remove from PATH in profile
+            remove_runtime_from_path("python", version)?;
+
+            // Remove from filesystem
+            remove_version("python", version)?;
+        }
+
+        // Ensure the version to keep is set as default
+        display_step(&format!("Setting {} as default", resolved_keep_version));
+        set_default_runtime("python", &resolved_keep_version)?;
+
+        // Reload profile to apply changes
+        reload_profile()?;
+
+        display_success(&format!("Pruned {} Python versions, kept {}", versions_to_remove.len(), resolved_keep_version));
+        Ok(())
+    }
+
+    fn set_default(&self, version: &str) -> Result<()> {
+        display_step(&format!("Setting Python {} as default", version));
+
+        // Resolve version first (e.g., "3.12" -> "3.12.8+20241206")
+        let resolved_version = resolve_installed_version("python", version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Set as default (has built-in messaging)
+        set_default_runtime("python", &resolved_version)?;
+
+        // Reload profile (has built-in messaging)
+        reload_profile()?;
+
+        // Force reload current environment
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("python", &resolved_version, "python3")?;
+
+        display_success(&format!("Python {} is now the default version", resolved_version));
+        Ok(())
+    }
+
+    fn use_version(&self, version: &str) -> Result<()> {
+        display_step(&format!("Switching to Python {} for current session", version));
+
+        // Resolve version first (e.g., "3.12" -> "3.12.8+20241206")
+        let resolved_version = resolve_installed_version("python", version)?;
+        display_success(&format!("Resolved to installed version: {}", resolved_version));
+
+        // Apply version to current session
+        display_step("Applying changes to current session");
+        apply_version_to_current_session("python", &resolved_version, "python3")?;
+
+        display_success(&format!("Python {} is now active in current session", resolved_version));
+        println!("💡 This change is temporary. To make it permanent, run:");
+        println!("   rvm set python {}", version);
+        Ok(())
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        list_installed_versions("python")
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        find_installed_version("python", version_input)
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        // Newest build of each Python version across the latest releases
+        display_step("Fetching builds of the latest python-build-standalone releases");
+        let platform = Platform::current()?;
+        let mut newest: BTreeMap<String, String> = BTreeMap::new();
+        for release in releases.iter().take(RELEASES_LISTED) {
+            for build in Self::release_builds(release, &platform)? {
+                let python_version = build.version.split('+').next().unwrap_or_default().to_string();
+                // Releases are searched newest first
+                newest.entry(python_version).or_insert(build.version);
+            }
+        }
+
+        let mut version_infos = Vec::new();
+        for python_version in newest.into_keys() {
+            if let Some(major_minor) = extract_major_minor(&python_version) {
+                version_infos.push(VersionInfo {
+                    channel: Self::channel(&python_version),
+                    version: python_version,
+                    major_minor,
+                });
+            }
+        }
+        version_infos.sort_by(|a, b| compare_versions(&b.version, &a.version));
+
+        display_success("Successfully retrieved and organized Python versions");
+        Ok(group_versions_by_channel(version_infos))
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        display_step("Fetching python-build-standalone releases from GitHub API");
+
+        let url = "https://api.github.com/repos/astral-sh/python-build-standalone/tags?per_page=100";
+        let client = blocking::Client::new();
+        let response = client
+            .get(url)
+            .header("User-Agent", "rvm-rust/1.0.0")
+            .send()?;
+
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch python-build-standalone releases: {}",
+                response.status()
+            )));
+        }
+
+        let json_data: serde_json::Value = response.json()?;
+        Ok(json_data)
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        // An installed name (e.g. from rvm.lock) is already exact
+        if version_input.contains('+') {
+            return Ok(version_input.to_string());
+        }
+        Ok(self.find_build(version_input)?.version)
+    }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest .python-version wins, the first line names the version (pyenv style)
+        for dir in start.ancestors() {
+            let path = dir.join(".python-version");
+            if path.is_file()
+                && let Some(version) = read_version_file(&path)?
+            {
+                // Other interpreters (e.g. "pypy3.10-7.3.17") and "system" aren't CPython
+                if Version::parse(&version).is_none() {
+                    return Ok(None);
+                }
+                return Ok(Some(VersionHint { version, source: path }));
+            }
+        }
+        Ok(None)
+    }
+}
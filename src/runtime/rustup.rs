@@ -0,0 +1,671 @@
+use super::Runtime;
+use crate::utils::download::{Platform, download_with_progress, make_executable, verify_sha256};
+use crate::utils::error::{Result, RvmError};
+use crate::utils::profile::{
+    add_runtime_env, reload_profile, remove_all_runtime_paths, set_default_runtime,
+};
+use crate::utils::project::{VersionHint, read_version_file};
+use crate::utils::semver::{Version, VersionReq, select_newest};
+use crate::utils::ui::{display_error, display_step, display_success};
+use crate::utils::version::{
+    VersionInfo, detect_channel, extract_major_minor, get_runtime_home, group_versions_by_channel,
+};
+use reqwest::blocking;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// rustup itself, installed with rustup-init into rvm's own RUSTUP_HOME and CARGO_HOME
+pub struct RustupRuntime;
+
+/// Rust toolchains, installed and switched through the rustup managed by RustupRuntime
+pub struct RustRuntime;
+
+/// Everything lives under the rust runtime home (~/.rust), away from a standalone ~/.rustup
+const RUNTIME_NAME: &str = "rust";
+
+/// Pseudo version whose bin directory (CARGO_HOME/bin, with the rustup proxies) is on PATH
+const CARGO_DIR: &str = "cargo";
+
+const RUSTUP_DIR: &str = "rustup";
+
+/// Toolchain channels rustup understands besides version numbers
+const CHANNELS: [&str; 3] = ["stable", "beta", "nightly"];
+
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    prerelease: bool,
+}
+
+/// Rust target triple of the host, rustup-init and toolchains are built for it
+fn host_triple(platform: &Platform) -> Result<String> {
+    let cpu = match platform.arch {
+        "x64" => "x86_64",
+        "arm64" => "aarch64",
+        arch => return Err(RvmError::UnsupportedArchitecture(arch.to_string())),
+    };
+    let libc = if platform.musl { "musl" } else { "gnu" };
+    Ok(format!("{}-unknown-linux-{}", cpu, libc))
+}
+
+fn rustup_home() -> Result<PathBuf> {
+    Ok(get_runtime_home(RUNTIME_NAME)?.join(RUSTUP_DIR))
+}
+
+fn cargo_home() -> Result<PathBuf> {
+    Ok(get_runtime_home(RUNTIME_NAME)?.join(CARGO_DIR))
+}
+
+/// RUSTUP_HOME and CARGO_HOME for rustup and the shells that use it
+fn rustup_env() -> Result<Vec<(&'static str, String)>> {
+    Ok(vec![
+        ("RUSTUP_HOME", rustup_home()?.to_string_lossy().to_string()),
+        ("CARGO_HOME", cargo_home()?.to_string_lossy().to_string()),
+    ])
+}
+
+/// The rustup installed by rvm, None if `rvm add rustup` hasn't run yet
+fn installed_rustup() -> Result<Option<PathBuf>> {
+    let rustup = cargo_home()?.join("bin").join("rustup");
+    Ok(rustup.is_file().then_some(rustup))
+}
+
+/// rustup with rvm's RUSTUP_HOME and CARGO_HOME, failing if it isn't installed
+fn rustup_command(args: &[&str]) -> Result<Command> {
+    let rustup = installed_rustup()?.ok_or_else(|| {
+        RvmError::BinaryNotFound("rustup is not installed, run `rvm add rustup` first".to_string())
+    })?;
+
+    let mut cmd = Command::new(rustup);
+    cmd.args(args).envs(rustup_env()?);
+    Ok(cmd)
+}
+
+/// Run rustup with its output going to the terminal
+fn run_rustup(args: &[&str]) -> Result<()> {
+    let status = rustup_command(args)?
+        .status()
+        .map_err(|e| RvmError::CommandExecutionFailed(e.to_string()))?;
+    if !status.success() {
+        return Err(RvmError::CommandExecutionFailed(format!(
+            "rustup {} failed ({})",
+            args.join(" "),
+            status
+        )));
+    }
+    Ok(())
+}
+
+/// Run rustup and capture its standard output
+fn rustup_output(args: &[&str]) -> Result<String> {
+    let output = rustup_command(args)?
+        .output()
+        .map_err(|e| RvmError::CommandExecutionFailed(e.to_string()))?;
+    if !output.status.success() {
+        return Err(RvmError::CommandExecutionFailed(format!(
+            "rustup {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Put CARGO_HOME/bin on PATH and export RUSTUP_HOME/CARGO_HOME in .profile. rustup-init runs
+/// with --no-modify-path, the profile entries are rvm's alone
+fn set_profile_entries() -> Result<()> {
+    set_default_runtime(RUNTIME_NAME, CARGO_DIR)?;
+    add_runtime_env(RUNTIME_NAME, &rustup_env()?)?;
+    reload_profile()
+}
+
+impl RustupRuntime {
+    /// Checksum published next to every rustup-init, as "<sha256>  <path>"
+    fn published_checksum(download_url: &str) -> Result<String> {
+        let checksum_url = format!("{}.sha256", download_url);
+        let response = blocking::get(&checksum_url)?;
+        if !response.status().is_success() {
+            return Err(RvmError::DownloadFailed(checksum_url));
+        }
+        Ok(response.text()?.split_whitespace().next().unwrap_or_default().to_string())
+    }
+
+    /// Installed rustup version (e.g. "1.27.1"), None if rustup isn't installed
+    fn installed_version(&self) -> Result<Option<String>> {
+        if installed_rustup()?.is_none() {
+            return Ok(None);
+        }
+
+        // "rustup 1.27.1 (54dd3d00f 2024-04-24)"
+        let output = rustup_output(&["--version"])?;
+        Ok(output.split_whitespace().nth(1).map(|version| version.to_string()))
+    }
+
+    /// Download rustup-init, verify it and run it non-interactively into rvm's homes
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
+        // Step 2: Check if already installed
+        if let Some(installed) = self.installed_version()? {
+            display_success(&format!("rustup {} is already installed", installed));
+            return Err(RvmError::VersionAlreadyInstalled(installed));
+        }
+
+        // Step 3: Download and verify rustup-init
+        let platform = Platform::current()?;
+        let download_url = self.artifact_url(resolved_version, &platform)?;
+        display_step(&format!("Downloading rustup-init {} for {}", resolved_version, host_triple(&platform)?));
+        let bytes = download_with_progress(&download_url)?;
+
+        let expected = match sha256 {
+            Some(sha256) => sha256.to_string(),
+            None => Self::published_checksum(&download_url)?,
+        };
+        verify_sha256(&bytes, &expected, &download_url)?;
+
+        let runtime_home = get_runtime_home(RUNTIME_NAME)?;
+        std::fs::create_dir_all(&runtime_home)?;
+        let rustup_init = runtime_home.join("rustup-init");
+        std::fs::write(&rustup_init, bytes)?;
+        make_executable(&rustup_init)?;
+
+        // Step 4: Install rustup and the stable toolchain without touching shell profiles
+        display_step("Running rustup-init");
+        let status = Command::new(&rustup_init)
+            .args(["-y", "--no-modify-path"])
+            .envs(rustup_env()?)
+            // A rustup or cargo already on PATH is expected, rvm's comes first once installed
+            .env("RUSTUP_INIT_SKIP_PATH_CHECK", "yes")
+            .status()
+            .map_err(|e| RvmError::CommandExecutionFailed(e.to_string()));
+        let _ = std::fs::remove_file(&rustup_init);
+        let status = status?;
+        if !status.success() {
+            display_error("rustup-init failed");
+            return Err(RvmError::CommandExecutionFailed(format!("rustup-init failed ({})", status)));
+        }
+
+        // Step 5: Set up PATH and the rustup environment
+        set_profile_entries()?;
+
+        display_success(&format!("rustup {} installation completed successfully!", resolved_version));
+        println!("💡 Manage toolchains with `rvm add rust <version>` and `rvm use rust <toolchain>`");
+        Ok(())
+    }
+}
+
+impl Runtime for RustupRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding rustup version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from the rustup release channel");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked rustup version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        Ok(format!(
+            "https://static.rust-lang.org/rustup/archive/{}/{}/rustup-init",
+            resolved_version,
+            host_triple(platform)?
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        resolved_version: &str,
+        platform: &Platform,
+    ) -> Result<Option<String>> {
+        let download_url = self.artifact_url(resolved_version, platform)?;
+        Self::published_checksum(&download_url).map(Some)
+    }
+
+    fn remove(&self, _version: Option<&str>) -> Result<()> {
+        display_step("Removing rustup and all Rust toolchains");
+
+        let runtime_home = get_runtime_home(RUNTIME_NAME)?;
+        if !runtime_home.exists() {
+            display_success("rustup is not installed");
+            return Ok(());
+        }
+
+        // Remove PATH and environment entries from profile
+        remove_all_runtime_paths(RUNTIME_NAME)?;
+
+        // RUSTUP_HOME and CARGO_HOME both live in the runtime home
+        std::fs::remove_dir_all(&runtime_home)?;
+
+        // Reload profile to apply changes
+        reload_profile()?;
+
+        display_success("Removed rustup and all Rust toolchains");
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating rustup to latest version");
+        run_rustup(&["self", "update"])?;
+        display_success("rustup is up to date");
+        Ok(())
+    }
+
+    fn prune(&self, _keep_version: &str) -> Result<()> {
+        Err(RvmError::InvalidArguments(
+            "rustup is a single installation, prune toolchains with `rvm prune rust <toolchain>`"
+                .to_string(),
+        ))
+    }
+
+    fn set_default(&self, _version: &str) -> Result<()> {
+        display_step("Restoring rustup's PATH and environment in ~/.profile");
+        if installed_rustup()?.is_none() {
+            return Err(RvmError::NoVersionsInstalled("rustup".to_string()));
+        }
+        set_profile_entries()?;
+        display_success("rustup is set up, switch toolchains with `rvm set rust <toolchain>`");
+        Ok(())
+    }
+
+    fn use_version(&self, _version: &str) -> Result<()> {
+        Err(RvmError::InvalidArguments(
+            "rustup has a single version, switch toolchains with `rvm use rust <toolchain>`"
+                .to_string(),
+        ))
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        Ok(self.installed_version()?.into_iter().collect())
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        let installed = self.installed_version()?;
+        Ok(installed.filter(|installed| {
+            version_input == "latest"
+                || installed == version_input
+                || select_newest([installed.as_str()], version_input).is_some()
+        }))
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let latest = self.resolve_version("latest")?;
+        Ok(vec![
+            "=== Stable Versions ===".to_string(),
+            format!("rustup {}", latest),
+            "Rust toolchains are listed by `rvm list available rust`".to_string(),
+        ])
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        display_step("Fetching the rustup release channel");
+
+        let response = blocking::get("https://static.rust-lang.org/rustup/release-stable.toml")?;
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch the rustup release: {}",
+                response.status()
+            )));
+        }
+
+        let release: toml::Value = toml::from_str(&response.text()?).map_err(|e| {
+            RvmError::VersionFetchFailed(format!("invalid rustup release-stable.toml: {}", e))
+        })?;
+        Ok(serde_json::json!({
+            "version": release.get("version").and_then(|version| version.as_str()),
+        }))
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        match version_input {
+            "latest" | "stable" => {
+                let json_data = self.fetch_available_versions()?;
+                json_data["version"]
+                    .as_str()
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::LatestVersionUnavailable("rustup".to_string()))
+            }
+            // Older rustup releases are archived by exact version (e.g. "1.27.1")
+            v => match Version::parse(v) {
+                Some(_) if v.split('.').count() == 3 => Ok(v.trim_start_matches('v').to_string()),
+                _ => Err(RvmError::InvalidVersionFormat(v.to_string())),
+            },
+        }
+    }
+
+    fn project_version(&self, _start: &Path) -> Result<Option<VersionHint>> {
+        // Projects pin toolchains (rust-toolchain.toml), not rustup itself
+        Ok(None)
+    }
+}
+
+impl RustRuntime {
+    /// Parse the GitHub releases API response
+    fn parse_api_response(json_data: &serde_json::Value) -> Result<Vec<GitHubRelease>> {
+        let releases: Vec<GitHubRelease> = serde_json::from_value(json_data.clone())?;
+        Ok(releases)
+    }
+
+    /// Installed toolchains, full names (e.g. "1.80.1-x86_64-unknown-linux-gnu")
+    fn toolchains(&self) -> Result<Vec<String>> {
+        let output = rustup_output(&["toolchain", "list"])?;
+        Ok(output
+            .lines()
+            // "stable-x86_64-unknown-linux-gnu (active, default)"
+            .filter_map(|line| line.split_whitespace().next())
+            .filter(|name| *name != "no")
+            .map(|name| name.to_string())
+            .collect())
+    }
+
+    /// Resolve version input against installed toolchains, reporting what is installed when
+    /// nothing matches
+    fn resolve_installed(&self, version_input: &str) -> Result<String> {
+        if let Some(toolchain) = self.find_installed(version_input)? {
+            return Ok(toolchain);
+        }
+
+        display_error(&format!("Rust {} is not installed", version_input));
+        println!("Available installed toolchains:");
+        for toolchain in self.toolchains()? {
+            println!("  ✓ {}", toolchain);
+        }
+
+        Err(RvmError::VersionNotFound(version_input.to_string()))
+    }
+
+    /// Read the channel from rust-toolchain.toml or a legacy rust-toolchain file
+    fn toolchain_file_channel(path: &std::path::Path) -> Result<Option<String>> {
+        let content = std::fs::read_to_string(path)?;
+        match toml::from_str::<toml::Value>(&content) {
+            Ok(config) => Ok(config
+                .get("toolchain")
+                .and_then(|toolchain| toolchain.get("channel"))
+                .and_then(|channel| channel.as_str())
+                .map(|channel| channel.to_string())),
+            // The legacy file holds only the toolchain name
+            Err(_) => read_version_file(path),
+        }
+    }
+}
+
+impl Runtime for RustRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("stable");
+        display_step(&format!("Adding Rust toolchain: {}", version_str));
+
+        let toolchain = self.resolve_version(version_str)?;
+        if let Some(installed) = self.find_installed(&toolchain)?
+            && !CHANNELS.contains(&toolchain.as_str())
+        {
+            display_success(&format!("Rust {} is already installed", installed));
+            return Err(RvmError::VersionAlreadyInstalled(installed));
+        }
+
+        // rustup downloads, verifies and unpacks the toolchain components
+        display_step(&format!("Installing Rust {} with rustup", toolchain));
+        run_rustup(&["toolchain", "install", &toolchain])?;
+
+        // Like the other runtimes, a newly added version becomes the default
+        run_rustup(&["default", &toolchain])?;
+
+        display_success(&format!("Rust {} installation completed successfully!", toolchain));
+        Ok(())
+    }
+
+    fn lockable(&self) -> bool {
+        // rustup downloads and verifies the toolchain components itself
+        false
+    }
+
+    fn add_locked(&self, _resolved_version: &str, _sha256: &str) -> Result<()> {
+        Err(RvmError::InvalidArguments(
+            "rust toolchains are verified by rustup, only their version is locked".to_string(),
+        ))
+    }
+
+    fn artifact_url(&self, _resolved_version: &str, _platform: &Platform) -> Result<String> {
+        Err(RvmError::InvalidArguments(
+            "rust toolchains are verified by rustup, only their version is locked".to_string(),
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        _resolved_version: &str,
+        _platform: &Platform,
+    ) -> Result<Option<String>> {
+        // Toolchains aren't lockable, rustup verifies them
+        Ok(None)
+    }
+
+    fn remove(&self, version: Option<&str>) -> Result<()> {
+        let toolchains = match version {
+            Some(v) => {
+                display_step(&format!("Removing Rust toolchain: {}", v));
+                vec![self.resolve_installed(v)?]
+            }
+            None => {
+                display_step("Removing all Rust toolchains");
+                self.toolchains()?
+            }
+        };
+
+        if toolchains.is_empty() {
+            display_success("No Rust toolchains are installed");
+            return Ok(());
+        }
+
+        for toolchain in &toolchains {
+            display_step(&format!("Removing Rust {}", toolchain));
+            run_rustup(&["toolchain", "uninstall", toolchain])?;
+        }
+
+        display_success(&format!("Removed {} Rust toolchains successfully", toolchains.len()));
+        Ok(())
+    }
+
+    fn update(&self) -> Result<()> {
+        display_step("Updating installed Rust channels");
+        run_rustup(&["update"])?;
+        display_success("Rust toolchains are up to date");
+        Ok(())
+    }
+
+    fn prune(&self, keep_version: &str) -> Result<()> {
+        display_step(&format!("Pruning Rust toolchains (keeping {})", keep_version));
+
+        let resolved_keep_version = self.resolve_installed(keep_version)?;
+        display_success(&format!("Will keep Rust toolchain: {}", resolved_keep_version));
+
+        let toolchains_to_remove: Vec<String> = self
+            .toolchains()?
+            .into_iter()
+            .filter(|toolchain| *toolchain != resolved_keep_version)
+            .collect();
+
+        if toolchains_to_remove.is_empty() {
+            display_success(&format!("Only {} is installed, nothing to prune", resolved_keep_version));
+            return Ok(());
+        }
+
+        display_step(&format!("Found {} Rust toolchains to remove", toolchains_to_remove.len()));
+        for toolchain in &toolchains_to_remove {
+            display_step(&format!("Removing Rust {}", toolchain));
+            run_rustup(&["toolchain", "uninstall", toolchain])?;
+        }
+
+        // Ensure the toolchain to keep is the default
+        run_rustup(&["default", &resolved_keep_version])?;
+
+        display_success(&format!("Pruned {} Rust toolchains, kept {}", toolchains_to_remove.len(), resolved_keep_version));
+        Ok(())
+    }
+
+    fn set_default(&self, version: &str) -> Result<()> {
+        display_step(&format!("Setting Rust {} as default", version));
+
+        let toolchain = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed toolchain: {}", toolchain));
+
+        // The rustup proxies on PATH follow rustup's default toolchain
+        run_rustup(&["default", &toolchain])?;
+
+        display_success(&format!("Rust {} is now the default toolchain", toolchain));
+        Ok(())
+    }
+
+    fn use_version(&self, version: &str) -> Result<()> {
+        display_step(&format!("Switching to Rust {} for current session", version));
+
+        let toolchain = self.resolve_installed(version)?;
+        display_success(&format!("Resolved to installed toolchain: {}", toolchain));
+
+        // The rustup proxies pick the toolchain from RUSTUP_TOOLCHAIN
+        let rustc_version = rustup_output(&["run", &toolchain, "rustc", "--version"])?;
+        println!("✅ rustc version: {}", rustc_version.trim());
+
+        display_success("Environment updated for current session");
+        println!("💡 To apply to your current shell, run:");
+        println!("   export RUSTUP_TOOLCHAIN=\"{}\"", toolchain);
+        println!("💡 This change is temporary. To make it permanent, run:");
+        println!("   rvm set rust {}", version);
+        Ok(())
+    }
+
+    fn list_installed(&self) -> Result<Vec<String>> {
+        self.toolchains()
+    }
+
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        if installed_rustup()?.is_none() {
+            return Ok(None);
+        }
+
+        let host = host_triple(&Platform::current()?)?;
+        let toolchains = self.toolchains()?;
+        let short_name = |toolchain: &str| {
+            let suffix = format!("-{}", host);
+            toolchain.strip_suffix(&suffix).unwrap_or(toolchain).to_string()
+        };
+
+        // Full names ("stable-x86_64-unknown-linux-gnu") and short names ("stable", "1.80.1")
+        if let Some(toolchain) = toolchains
+            .iter()
+            .find(|toolchain| **toolchain == version_input || short_name(toolchain) == version_input)
+        {
+            return Ok(Some(toolchain.clone()));
+        }
+
+        // Partial versions and ranges ("1.80", "^1.79") against the numbered toolchains
+        let short_names: Vec<String> = toolchains.iter().map(|t| short_name(t)).collect();
+        let newest = select_newest(short_names.iter().map(String::as_str), version_input);
+        Ok(newest.and_then(|newest| {
+            toolchains
+                .iter()
+                .find(|toolchain| short_name(toolchain) == newest)
+                .cloned()
+        }))
+    }
+
+    fn list_available(&self) -> Result<Vec<String>> {
+        let json_data = self.fetch_available_versions()?;
+        let releases = Self::parse_api_response(&json_data)?;
+
+        let version_infos = releases
+            .into_iter()
+            .filter(|release| !release.prerelease)
+            .filter_map(|release| {
+                let major_minor = extract_major_minor(&release.tag_name)?;
+                Some(VersionInfo {
+                    channel: detect_channel(&release.tag_name, false),
+                    version: release.tag_name,
+                    major_minor,
+                })
+            })
+            .collect();
+
+        let mut result = vec![
+            "=== Channels ===".to_string(),
+            format!("{} (or a dated nightly such as nightly-2024-08-01)", CHANNELS.join(", ")),
+            String::new(),
+        ];
+        result.extend(group_versions_by_channel(version_infos));
+
+        display_success("Successfully retrieved and organized Rust versions");
+        Ok(result)
+    }
+
+    fn fetch_available_versions(&self) -> Result<serde_json::Value> {
+        display_step("Fetching Rust versions from GitHub releases API");
+
+        let url = "https://api.github.com/repos/rust-lang/rust/releases?per_page=100";
+        let client = blocking::Client::new();
+        let response = client
+            .get(url)
+            .header("User-Agent", "rvm-rust/1.0.0")
+            .send()?;
+
+        if !response.status().is_success() {
+            return Err(RvmError::HttpError(format!(
+                "Failed to fetch Rust releases: {}",
+                response.status()
+            )));
+        }
+
+        let json_data: serde_json::Value = response.json()?;
+        Ok(json_data)
+    }
+
+    fn resolve_version(&self, version_input: &str) -> Result<String> {
+        // rustup resolves channels, dated nightlies and partial versions ("1.80") itself
+        let is_channel = CHANNELS
+            .iter()
+            .any(|channel| version_input.split('-').next() == Some(*channel));
+        let version = version_input.trim_start_matches('v');
+        let parts: Vec<&str> = version.split('.').collect();
+        let is_partial = (2..=3).contains(&parts.len())
+            && parts
+                .iter()
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+        match version_input {
+            "latest" => Ok("stable".to_string()),
+            v if is_channel => Ok(v.to_string()),
+            _ if is_partial => Ok(version.to_string()),
+            v => {
+                // Ranges (e.g. "1", ">=1.75 <1.80") pick the newest matching stable release
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                let json_data = self.fetch_available_versions()?;
+                let releases = Self::parse_api_response(&json_data)?;
+                let stable = releases
+                    .iter()
+                    .filter(|release| !release.prerelease)
+                    .map(|release| release.tag_name.as_str());
+                select_newest(stable, v)
+                    .map(|version| version.to_string())
+                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
+            }
+        }
+    }
+
+    fn project_version(&self, start: &Path) -> Result<Option<VersionHint>> {
+        // Nearest directory wins, rust-toolchain.toml > rust-toolchain
+        for dir in start.ancestors() {
+            for file_name in ["rust-toolchain.toml", "rust-toolchain"] {
+                let path = dir.join(file_name);
+                if path.is_file()
+                    && let Some(version) = Self::toolchain_file_channel(&path)?
+                {
+                    return Ok(Some(VersionHint { version, source: path }));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
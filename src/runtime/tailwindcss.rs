@@ -1,14 +1,17 @@
 use super::Runtime;
-use crate::utils::download::get_architecture;
+use crate::utils::download::{Platform, verify_sha256};
 use crate::utils::error::{Result, RvmError};
 use crate::utils::profile::{reload_profile, remove_runtime_from_path, set_default_runtime};
+use crate::utils::project::VersionHint;
+use crate::utils::semver::{VersionReq, select_newest};
 use crate::utils::ui::{display_error, display_step, display_success};
 use crate::utils::version::{
-    apply_version_to_current_session, get_runtime_home, is_version_installed,
-    list_installed_versions, remove_version, resolve_installed_version,
+    apply_version_to_current_session, compare_versions, find_installed_version, get_runtime_home,
+    is_version_installed, list_installed_versions, remove_version, resolve_installed_version,
 };
 use reqwest::blocking;
 use serde::Deserialize;
+use std::path::Path;
 
 /// TailwindCSS runtime manager
 pub struct TailwindCssRuntime;
@@ -35,38 +38,19 @@ impl TailwindCssRuntime {
         };
         Ok(format!("tailwindcss-{}", tailwind_arch))
     }
-}
-
-impl Runtime for TailwindCssRuntime {
-    fn add(&self, version: Option<&str>) -> Result<()> {
-        let version_str = version.unwrap_or("latest");
-        display_step(&format!("Adding TailwindCSS version: {}", version_str));
-
-        // Step 1: Parse and resolve version
-        display_step("Resolving version from GitHub releases API");
-        let resolved_version = self.resolve_version(version_str)?;
-        display_success(&format!("Resolved to version: {}", resolved_version));
 
+    /// Download and activate an exact TailwindCSS release, optionally verifying its checksum
+    fn install_resolved(&self, resolved_version: &str, sha256: Option<&str>) -> Result<()> {
         // Step 2: Check if already installed
-        if is_version_installed("tailwindcss", &resolved_version)? {
-            return Err(RvmError::VersionAlreadyInstalled(resolved_version));
+        if is_version_installed("tailwindcss", resolved_version)? {
+            return Err(RvmError::VersionAlreadyInstalled(resolved_version.to_string()));
         }
 
         // Step 3: Download the standalone executable
-        let arch = get_architecture()?;
-        let executable_filename = self.get_executable_filename(&arch)?;
-        let download_url = format!(
-            "https://github.com/tailwindlabs/tailwindcss/releases/download/{}/{}",
-            resolved_version, executable_filename
-        );
+        let download_url = self.artifact_url(resolved_version, &Platform::current()?)?;
 
         display_step(&format!("Downloading TailwindCSS {} executable", resolved_version));
 
-        // Create the runtime directory structure
-        let runtime_home = get_runtime_home("tailwindcss")?;
-        let version_dir = runtime_home.join(&resolved_version).join("bin");
-        std::fs::create_dir_all(&version_dir)?;
-
         // Download the executable directly
         let client = blocking::Client::new();
         let response = client.get(&download_url).send()?;
@@ -78,8 +62,17 @@ impl Runtime for TailwindCssRuntime {
             )));
         }
 
-        let executable_path = version_dir.join("tailwindcss");
         let executable_bytes = response.bytes()?;
+        if let Some(expected) = sha256 {
+            verify_sha256(&executable_bytes, expected, &download_url)?;
+        }
+
+        // Create the runtime directory structure
+        let runtime_home = get_runtime_home("tailwindcss")?;
+        let version_dir = runtime_home.join(resolved_version).join("bin");
+        std::fs::create_dir_all(&version_dir)?;
+
+        let executable_path = version_dir.join("tailwindcss");
         std::fs::write(&executable_path, executable_bytes)?;
 
         // Make executable
@@ -94,7 +87,7 @@ impl Runtime for TailwindCssRuntime {
         display_success(&format!("Downloaded TailwindCSS {} executable", resolved_version));
 
         // Step 4: Set as default
-        set_default_runtime("tailwindcss", &resolved_version)?;
+        set_default_runtime("tailwindcss", resolved_version)?;
 
         // Step 5: Automatically reload profile
         reload_profile()?;
@@ -102,6 +95,42 @@ impl Runtime for TailwindCssRuntime {
         display_success(&format!("TailwindCSS {} installation completed successfully!", resolved_version));
         Ok(())
     }
+}
+
+impl Runtime for TailwindCssRuntime {
+    fn add(&self, version: Option<&str>) -> Result<()> {
+        let version_str = version.unwrap_or("latest");
+        display_step(&format!("Adding TailwindCSS version: {}", version_str));
+
+        // Step 1: Parse and resolve version
+        display_step("Resolving version from GitHub releases API");
+        let resolved_version = self.resolve_version(version_str)?;
+        display_success(&format!("Resolved to version: {}", resolved_version));
+
+        self.install_resolved(&resolved_version, None)
+    }
+
+    fn add_locked(&self, resolved_version: &str, sha256: &str) -> Result<()> {
+        display_step(&format!("Adding locked TailwindCSS version: {}", resolved_version));
+        self.install_resolved(resolved_version, Some(sha256))
+    }
+
+    fn artifact_url(&self, resolved_version: &str, platform: &Platform) -> Result<String> {
+        let executable_filename = self.get_executable_filename(platform.arch)?;
+        Ok(format!(
+            "https://github.com/tailwindlabs/tailwindcss/releases/download/{}/{}",
+            resolved_version, executable_filename
+        ))
+    }
+
+    fn published_sha256(
+        &self,
+        _resolved_version: &str,
+        _platform: &Platform,
+    ) -> Result<Option<String>> {
+        // Older releases come without a checksum list, rvm lock hashes the first download
+        Ok(None)
+    }
 
     fn remove(&self, version: Option<&str>) -> Result<()> {
         match version {
@@ -147,17 +176,14 @@ impl Runtime for TailwindCssRuntime {
 
                 // Clean up the entire tailwindcss directory if it's empty
                 let runtime_home = get_runtime_home("tailwindcss")?;
-                if runtime_home.exists() {
-                    match std::fs::read_dir(&runtime_home) {
-                        Ok(mut entries) => {
-                            if entries.next().is_none() {
-                                display_step("Cleaning up empty TailwindCSS directory");
-                                std::fs::remove_dir(&runtime_home)?;
-                                display_success("Removed empty TailwindCSS directory");
-                            }
-                        }
-                        Err(_) => {} // Directory doesn't exist or can't read, skip cleanup
-                    }
+                // Skip cleanup if the directory doesn't exist or can't be read
+                if runtime_home.exists()
+                    && let Ok(mut entries) = std::fs::read_dir(&runtime_home)
+                    && entries.next().is_none()
+                {
+                    display_step("Cleaning up empty TailwindCSS directory");
+                    std::fs::remove_dir(&runtime_home)?;
+                    display_success("Removed empty TailwindCSS directory");
                 }
 
                 // Reload profile to apply changes
@@ -289,6 +315,10 @@ impl Runtime for TailwindCssRuntime {
         list_installed_versions("tailwindcss")
     }
 
+    fn find_installed(&self, version_input: &str) -> Result<Option<String>> {
+        find_installed_version("tailwindcss", version_input)
+    }
+
     fn list_available(&self) -> Result<Vec<String>> {
         let json_data = self.fetch_available_versions()?;
         let releases = Self::parse_api_response(&json_data)?;
@@ -320,13 +350,13 @@ impl Runtime for TailwindCssRuntime {
                 std::collections::BTreeMap::new();
 
             for version in &stable_versions {
-                let version_clean = version.strip_prefix('v').unwrap_or(&version);
+                let version_clean = version.strip_prefix('v').unwrap_or(version);
                 let parts: Vec<&str> = version_clean.split('.').collect();
                 if parts.len() >= 2 {
                     let major_minor = format!("{}.{}", parts[0], parts[1]);
                     major_minor_groups
                         .entry(major_minor)
-                        .or_insert_with(Vec::new)
+                        .or_default()
                         .push(version.clone());
                 }
             }
@@ -336,7 +366,7 @@ impl Runtime for TailwindCssRuntime {
             sorted_groups.sort_by(|a, b| {
                 let parse_major_minor = |s: &str| -> (u32, u32) {
                     let parts: Vec<&str> = s.split('.').collect();
-                    let major = parts.get(0).and_then(|s| s.parse().ok()).unwrap_or(0);
+                    let major = parts.first().and_then(|s| s.parse().ok()).unwrap_or(0);
                     let minor = parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0);
                     (major, minor)
                 };
@@ -346,25 +376,12 @@ impl Runtime for TailwindCssRuntime {
             });
 
             // Display top 4 major.minor groups
-            let mut count = 0;
-            for (major_minor, mut versions) in sorted_groups {
-                if count >= 4 {
-                    break;
-                }
-
+            for (major_minor, mut versions) in sorted_groups.into_iter().take(4) {
                 // Sort versions within the group (newest first)
-                versions.sort_by(|a, b| {
-                    let parse_patch = |v: &str| -> u32 {
-                        let version_clean = v.strip_prefix('v').unwrap_or(v);
-                        let parts: Vec<&str> = version_clean.split('.').collect();
-                        parts.get(2).and_then(|s| s.parse().ok()).unwrap_or(0)
-                    };
-                    parse_patch(b).cmp(&parse_patch(a))
-                });
+                versions.sort_by(|a, b| compare_versions(b, a));
 
                 let versions_str = versions.join(", ");
                 result.push(format!("{}: {}", major_minor, versions_str));
-                count += 1;
             }
 
             result.push("... displaying top 4 versions, all other versions truncated".to_string());
@@ -422,41 +439,19 @@ impl Runtime for TailwindCssRuntime {
                     .map(|r| r.tag_name.clone())
                     .ok_or_else(|| RvmError::VersionNotFound("latest".to_string()))
             }
-            v if v.chars().all(|c| c.is_ascii_digit()) => {
-                // Major version only (e.g., "3")
-                let target_major = format!("v{}", v);
-                available_versions
-                    .iter()
-                    .find(|r| r.tag_name.starts_with(&target_major) && !r.prerelease)
-                    .map(|r| r.tag_name.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
-            }
-            v if v.matches('.').count() == 1 => {
-                // Major.minor version (e.g., "3.4")
-                let target_prefix = if v.starts_with('v') {
-                    v.to_string()
-                } else {
-                    format!("v{}", v)
-                };
-                available_versions
-                    .iter()
-                    .find(|r| r.tag_name.starts_with(&target_prefix) && !r.prerelease)
-                    .map(|r| r.tag_name.clone())
-                    .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
-            }
             v => {
-                // Specific version (e.g., "3.4.0" or "v3.4.0")
-                let target_version = if v.starts_with('v') {
-                    v.to_string()
-                } else {
-                    format!("v{}", v)
-                };
-                available_versions
-                    .iter()
-                    .find(|r| r.tag_name == target_version)
-                    .map(|r| r.tag_name.clone())
+                // Exact version, partial version or range (e.g., "3.4.0", "v4.0.0-beta.8", "3.4",
+                // "^3"), newest matching release wins
+                VersionReq::parse(v).ok_or_else(|| RvmError::InvalidVersionFormat(v.to_string()))?;
+                select_newest(available_versions.iter().map(|r| r.tag_name.as_str()), v)
+                    .map(|version| version.to_string())
                     .ok_or_else(|| RvmError::VersionNotFound(v.to_string()))
             }
         }
     }
-}
\ No newline at end of file
+
+    fn project_version(&self, _start: &Path) -> Result<Option<VersionHint>> {
+        // No runtime specific version files, only rvm.toml pins apply
+        Ok(None)
+    }
+}
@@ -1,4 +1,7 @@
-use clap::{Parser, Subcommand};
+use crate::utils::watch_events::EventFormat;
+use crate::utils::watcher::{RestartPolicy, StopSignal};
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "rvm")]
@@ -13,11 +16,19 @@ pub struct Cli {
 pub enum Commands {
     /// Install a runtime
     Add {
-        /// Runtime to install (node, deno, bun, cpython, pypy, golang, rustup)
+        /// Runtime to install (node, deno, bun, python, pypy, go, rustup)
         runtime: String,
-        /// Version to install (defaults to latest)
+        /// Version to install (defaults to the project's version, then latest)
         version: Option<String>,
     },
+    /// Install every runtime pinned in the project's rvm.toml or .tool-versions
+    Install {
+        /// Install exactly the versions recorded in rvm.lock, failing if it is out of date
+        #[arg(long)]
+        locked: bool,
+    },
+    /// Resolve the project's pinned runtimes and record them in rvm.lock
+    Lock,
     /// Remove a runtime or specific version
     Remove {
         /// Runtime to remove
@@ -48,15 +59,11 @@ pub enum Commands {
     Use {
         /// Runtime to use
         runtime: String,
-        /// Version to use
-        version: String,
+        /// Version to use (defaults to the project's version, e.g. from rvm.toml or .nvmrc)
+        version: Option<String>,
     },
     /// Watch for file changes and restart command
-    Watch {
-        /// Command and arguments (including -<seconds> for interval)
-        #[arg(required = true, allow_hyphen_values = true)]
-        args: Vec<String>,
-    },
+    Watch(Box<WatchArgs>),
     /// List runtimes, available versions, or installed versions
     List {
         /// Arguments (runtimes | available <runtime> | installed <runtime>)
@@ -65,6 +72,114 @@ pub enum Commands {
     },
 }
 
+/// Options of `rvm watch`
+#[derive(Args)]
+pub struct WatchArgs {
+    /// Milliseconds without changes to wait before restarting
+    #[arg(long, default_value_t = 200)]
+    pub debounce: u64,
+    /// Minimum seconds between two restarts
+    #[arg(long, value_name = "SECONDS")]
+    pub delay: Option<u64>,
+    /// Gitignore-style glob of paths to ignore, in addition to .gitignore and .rvmignore
+    #[arg(long, value_name = "GLOB")]
+    pub ignore: Vec<String>,
+    /// Directory or file to watch, can be repeated (defaults to the current directory)
+    #[arg(long = "watch", value_name = "PATH")]
+    pub watch_paths: Vec<PathBuf>,
+    /// Only restart for files with these extensions (e.g. go,mod)
+    #[arg(long, value_delimiter = ',')]
+    pub ext: Vec<String>,
+    /// Only restart for files matching this gitignore-style glob, can be repeated
+    #[arg(long, value_name = "GLOB")]
+    pub include: Vec<String>,
+    /// Signal sent to the command's process group to stop it
+    #[arg(long, value_enum, ignore_case = true, default_value = "SIGTERM")]
+    pub signal: StopSignal,
+    /// Milliseconds the command gets to exit after the signal before it is killed
+    #[arg(long, default_value_t = 5000)]
+    pub grace_period: u64,
+    /// Build command run on each change before restarting; if it fails the running command
+    /// is kept
+    #[arg(long, value_name = "COMMAND")]
+    pub build: Option<String>,
+    /// Restart the command when it exits by itself
+    #[arg(long, value_enum, default_value = "never")]
+    pub restart: RestartPolicy,
+    /// Restarts in a row (with exponential backoff) before waiting for changes instead
+    #[arg(long, default_value_t = 5)]
+    pub max_retries: u32,
+    /// Stop watching once the command exits successfully (e.g. a test runner)
+    #[arg(long)]
+    pub exit_on_success: bool,
+    /// Don't read keyboard controls from the terminal, the command keeps its stdin. It runs in
+    /// its own process group, so it can read piped input but is stopped if it reads the terminal
+    #[arg(long)]
+    pub no_keys: bool,
+    /// Poll for changes every <MS> milliseconds instead of using inotify (for NFS or Docker
+    /// bind mounts). rvm also polls by itself when the inotify watch limit is reached
+    #[arg(
+        long,
+        value_name = "MS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1000"
+    )]
+    pub poll: Option<u64>,
+    /// Variable to set for the command, can be repeated. Wins over .env files
+    #[arg(long, value_name = "KEY=VALUE")]
+    pub env: Vec<String>,
+    /// Also load .env.<PROFILE> (e.g. development), on top of .env
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// Report the command as ready once this localhost port accepts connections
+    #[arg(long, value_name = "PORT", conflicts_with_all = ["ready_log", "ready_url"])]
+    pub ready_port: Option<u16>,
+    /// Report the command as ready once a line of its output matches this regex
+    #[arg(long, value_name = "REGEX", conflicts_with = "ready_url")]
+    pub ready_log: Option<String>,
+    /// Report the command as ready once this URL answers with a 2xx status
+    #[arg(long, value_name = "URL")]
+    pub ready_url: Option<String>,
+    /// Milliseconds the command gets to become ready
+    #[arg(long, default_value_t = 30000)]
+    pub ready_timeout: u64,
+    /// Serve live reload events to browsers on this port (35729 by default, the port livereload
+    /// tools use)
+    #[arg(
+        long,
+        value_name = "PORT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "35729"
+    )]
+    pub livereload: Option<u16>,
+    /// Write change, restart, exit and ready events in this format to --events-file
+    #[arg(long, value_enum, requires = "events_file")]
+    pub events: Option<EventFormat>,
+    /// File or FIFO the events are appended to
+    #[arg(long, value_name = "PATH", requires = "events")]
+    pub events_file: Option<PathBuf>,
+    /// Shell command run after each batch of changes, with the paths in RVM_CHANGED_PATHS
+    #[arg(long, value_name = "COMMAND")]
+    pub on_change: Option<String>,
+    /// Shell command run each time the command exits, with RVM_EXIT_CODE or RVM_EXIT_SIGNAL
+    #[arg(long, value_name = "COMMAND")]
+    pub on_exit: Option<String>,
+    /// Command to run, either a single shell command line or a program and its arguments.
+    /// Runs the [watch.<name>] tasks from rvm.toml when omitted
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
+
+impl WatchArgs {
+    /// Whether the command was given after `--`, which clap drops from the parsed arguments
+    pub fn command_follows_separator(&self) -> bool {
+        let raw: Vec<String> = std::env::args().collect();
+        raw.len() > self.command.len() && raw[raw.len() - self.command.len() - 1] == "--"
+    }
+}
+
 pub fn parse() -> Cli {
     Cli::parse()
 }
\ No newline at end of file
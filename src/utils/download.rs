@@ -3,12 +3,77 @@ use crate::utils::version::get_runtime_home;
 use crate::utils::ui::{display_step, display_success, display_error};
 use reqwest::blocking;
 use std::fs;
-use std::io::{Read, Write};
+use std::io::{self, Cursor, Read, Write};
 use std::os::unix::fs::PermissionsExt;
-use std::path::PathBuf;
+use std::path::{Component, Path, PathBuf};
 use tar::Archive;
 use xz2::read::XzDecoder;
 use flate2::read::GzDecoder;
+use bzip2::read::BzDecoder;
+use sha2::{Digest, Sha256};
+use zip::ZipArchive;
+
+/// Architectures recorded in lockfiles, in the format returned by `get_architecture`
+pub const SUPPORTED_ARCHITECTURES: &[&str] = &["x64", "arm64"];
+
+/// Build variant a download is made for. Runtimes that publish one build per architecture only
+/// look at `arch`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Platform {
+    /// Architecture in the format returned by `get_architecture`
+    pub arch: &'static str,
+    /// musl libc (e.g. Alpine) rather than glibc
+    pub musl: bool,
+    /// x64 CPU without AVX2, for runtimes that publish baseline builds
+    pub baseline: bool,
+}
+
+impl Platform {
+    /// The variant this machine needs
+    pub fn current() -> Result<Self> {
+        let arch = get_architecture()?;
+        Ok(Platform {
+            arch,
+            musl: is_musl(),
+            baseline: arch == "x64" && !has_avx2(),
+        })
+    }
+
+    /// Every variant recorded in lockfiles
+    pub fn all() -> Vec<Self> {
+        let mut platforms = Vec::new();
+        for arch in SUPPORTED_ARCHITECTURES {
+            for musl in [false, true] {
+                for baseline in [false, true] {
+                    // Baseline builds only exist for x64
+                    if !baseline || *arch == "x64" {
+                        platforms.push(Platform { arch, musl, baseline });
+                    }
+                }
+            }
+        }
+        platforms
+    }
+
+    /// Name used as lockfile key (e.g. "x64", "arm64-musl", "x64-musl-baseline")
+    pub fn key(&self) -> String {
+        let musl = if self.musl { "-musl" } else { "" };
+        let baseline = if self.baseline { "-baseline" } else { "" };
+        format!("{}{}{}", self.arch, musl, baseline)
+    }
+}
+
+/// Whether the CPU supports AVX2, which the default x64 builds of some runtimes require
+#[cfg(target_arch = "x86_64")]
+fn has_avx2() -> bool {
+    std::arch::is_x86_feature_detected!("avx2")
+}
+
+/// Only x64 CPUs are checked for AVX2
+#[cfg(not(target_arch = "x86_64"))]
+fn has_avx2() -> bool {
+    true
+}
 
 /// Get the system architecture in the format expected by runtime distributors
 pub fn get_architecture() -> Result<&'static str> {
@@ -19,6 +84,18 @@ pub fn get_architecture() -> Result<&'static str> {
     }
 }
 
+/// Whether the system C library is musl (e.g. Alpine) rather than glibc, for runtimes that
+/// publish separate musl builds
+pub fn is_musl() -> bool {
+    ["/lib", "/usr/lib"].iter().any(|dir| {
+        fs::read_dir(dir).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.file_name().to_string_lossy().starts_with("ld-musl-"))
+        })
+    })
+}
+
 /// Make a binary file executable
 pub fn make_executable(binary_path: &PathBuf) -> Result<()> {
     if binary_path.exists() {
@@ -44,16 +121,8 @@ fn display_download_progress(downloaded: u64, total: Option<u64>) {
     }
 }
 
-/// Download and extract a runtime archive with progress indicators
-pub fn download_and_extract(
-    download_url: &str,
-    runtime_name: &str,
-    version: &str,
-    binary_paths: &[&str], // Relative paths to binaries that need to be executable
-) -> Result<()> {
-    display_step(&format!("Starting download of {} {}", runtime_name, version));
-    
-    // Download with progress
+/// Download a file into memory with progress indicators
+pub fn download_with_progress(download_url: &str) -> Result<Vec<u8>> {
     let response = blocking::get(download_url)?;
     if !response.status().is_success() {
         display_error(&format!("Download failed: HTTP {}", response.status()));
@@ -86,6 +155,75 @@ pub fn download_and_extract(
     
     println!(); // New line after progress bar
     display_success(&format!("Downloaded {:.1}MB successfully", downloaded as f64 / 1024.0 / 1024.0));
+    Ok(bytes)
+}
+
+/// Hex encoded SHA-256 digest of the given bytes
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Whether a download exists, false when the server answers 404 (e.g. a build variant a release
+/// doesn't ship)
+pub fn is_published(download_url: &str) -> Result<bool> {
+    let response = blocking::Client::new().head(download_url).send()?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(false);
+    }
+    if !response.status().is_success() {
+        return Err(RvmError::HttpError(format!(
+            "Failed to check {}: {}",
+            download_url,
+            response.status()
+        )));
+    }
+    Ok(true)
+}
+
+/// Digest of a file in a published checksum list (`<sha256>  <file name>` per line, as written
+/// by sha256sum)
+pub fn find_checksum(listing: &str, file_name: &str) -> Option<String> {
+    listing.lines().find_map(|line| {
+        let (sha256, name) = line.split_once(char::is_whitespace)?;
+        // sha256sum marks files read in binary mode with '*'
+        let name = name.trim_start().trim_start_matches('*');
+        (name == file_name).then(|| sha256.to_string())
+    })
+}
+
+/// Verify downloaded bytes against an expected SHA-256 digest
+pub fn verify_sha256(bytes: &[u8], expected: &str, download_url: &str) -> Result<()> {
+    display_step("Verifying SHA-256 checksum");
+    let actual = sha256_hex(bytes);
+    if !actual.eq_ignore_ascii_case(expected) {
+        display_error("Checksum mismatch");
+        return Err(RvmError::ChecksumMismatch {
+            url: download_url.to_string(),
+            expected: expected.to_string(),
+            actual,
+        });
+    }
+    display_success("Checksum verified");
+    Ok(())
+}
+
+/// Download and extract a runtime archive with progress indicators
+pub fn download_and_extract(
+    download_url: &str,
+    runtime_name: &str,
+    version: &str,
+    binary_paths: &[&str], // Relative paths to binaries that need to be executable
+    expected_sha256: Option<&str>, // Checksum to verify before extracting, e.g. from rvm.lock
+) -> Result<()> {
+    display_step(&format!("Starting download of {} {}", runtime_name, version));
+    
+    let bytes = download_with_progress(download_url)?;
+    if let Some(expected) = expected_sha256 {
+        verify_sha256(&bytes, expected, download_url)?;
+    }
     
     // Create runtime directory if it doesn't exist
     display_step("Creating installation directory");
@@ -107,6 +245,12 @@ pub fn download_and_extract(
         let decoder = GzDecoder::new(bytes.as_slice());
         let mut archive = Archive::new(decoder);
         archive.unpack(&temp_dir)?;
+    } else if download_url.ends_with(".tar.bz2") {
+        let decoder = BzDecoder::new(bytes.as_slice());
+        let mut archive = Archive::new(decoder);
+        archive.unpack(&temp_dir)?;
+    } else if download_url.ends_with(".zip") {
+        extract_zip(&bytes, &temp_dir)?;
     } else {
         display_error("Unsupported archive format");
         return Err(RvmError::ExtractionFailed("Unsupported archive format".to_string()));
@@ -137,4 +281,110 @@ pub fn download_and_extract(
     
     display_success(&format!("Successfully installed {} {}", runtime_name, version));
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Extract a zip archive into a directory, keeping the unix permissions recorded for each entry
+pub fn extract_zip(bytes: &[u8], destination: &Path) -> Result<()> {
+    let invalid =
+        |reason: String| RvmError::ExtractionFailed(format!("invalid zip archive: {}", reason));
+
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(|e| invalid(e.to_string()))?;
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| invalid(e.to_string()))?;
+
+        // Never write outside the destination
+        let relative = Path::new(entry.name());
+        if !relative.components().all(|part| matches!(part, Component::Normal(_))) {
+            return Err(invalid(format!("unsafe entry path {}", entry.name())));
+        }
+        let path = destination.join(relative);
+
+        if entry.is_dir() {
+            fs::create_dir_all(&path)?;
+            continue;
+        }
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = fs::File::create(&path)?;
+        io::copy(&mut entry, &mut file)?;
+        if let Some(mode) = entry.unix_mode()
+            && mode & 0o777 != 0
+        {
+            fs::set_permissions(&path, fs::Permissions::from_mode(mode & 0o777))?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zip::CompressionMethod;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    /// Zip archive with the given entries, directories end with '/'
+    fn archive(entries: &[(&str, &[u8], CompressionMethod)]) -> Vec<u8> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, contents, method) in entries {
+            let options = SimpleFileOptions::default()
+                .compression_method(*method)
+                .unix_permissions(0o755);
+            if name.ends_with('/') {
+                writer.add_directory(*name, options).unwrap();
+            } else {
+                writer.start_file(*name, options).unwrap();
+                writer.write_all(contents).unwrap();
+            }
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Empty directory to extract into, removed again by the caller
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("rvm-test-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn extracts_stored_and_deflated_entries() {
+        let contents = b"#!/bin/sh\necho hello\n".repeat(50);
+        let bytes = archive(&[
+            ("bin/", b"", CompressionMethod::Stored),
+            ("bin/stored", &contents, CompressionMethod::Stored),
+            ("bin/deflated", &contents, CompressionMethod::Deflated),
+        ]);
+
+        let dir = scratch_dir("zip-extract");
+        extract_zip(&bytes, &dir).unwrap();
+        for name in ["stored", "deflated"] {
+            let path = dir.join("bin").join(name);
+            assert_eq!(fs::read(&path).unwrap(), contents);
+            assert_eq!(fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o755);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_entries_outside_the_destination() {
+        let dir = scratch_dir("zip-unsafe");
+        for name in ["../escaped", "bin/../../escaped", "/tmp/escaped"] {
+            let bytes = archive(&[(name, b"x", CompressionMethod::Stored)]);
+            assert!(extract_zip(&bytes, &dir).is_err(), "{} was extracted", name);
+        }
+        assert!(!dir.parent().unwrap().join("escaped").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rejects_truncated_archives() {
+        let bytes = archive(&[("deno", b"binary", CompressionMethod::Deflated)]);
+        let dir = scratch_dir("zip-truncated");
+        assert!(extract_zip(&bytes[..bytes.len() / 2], &dir).is_err());
+        assert!(extract_zip(b"not a zip archive", &dir).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
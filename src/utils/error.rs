@@ -39,6 +39,9 @@ pub enum RvmError {
     #[error("Download failed: {0}")]
     DownloadFailed(String),
     
+    #[error("Checksum mismatch for {url}: expected {expected}, got {actual}")]
+    ChecksumMismatch { url: String, expected: String, actual: String },
+    
     #[error("Failed to fetch available versions: {0}")]
     VersionFetchFailed(String),
     
@@ -83,6 +86,9 @@ pub enum RvmError {
     
     #[error("Configuration file error: {0}")]
     ConfigurationError(String),
+    
+    #[error("rvm.lock is out of date: {0}. Run `rvm lock` to update it")]
+    LockfileMismatch(String),
 
     // CLI/Input-related errors
     #[error("Invalid command arguments: {0}")]
@@ -0,0 +1,145 @@
+use super::download::Platform;
+use super::error::{Result, RvmError};
+use super::lock::{LOCK_FILE, LockFile};
+use super::project::ProjectConfig;
+use super::ui::{display_error, display_step, display_success};
+use crate::runtime;
+
+/// Install every runtime pinned by the project and activate it for the session. With `locked`,
+/// install exactly the versions recorded in rvm.lock and verify their checksums
+pub fn handle_install_command(locked: bool) -> Result<()> {
+    let config = ProjectConfig::find()?.ok_or_else(|| {
+        RvmError::FileNotFound(
+            "no rvm.toml or .tool-versions found in this directory or its parents".to_string(),
+        )
+    })?;
+
+    display_step(&format!("Using project pins from {}", config.file.display()));
+
+    if config.runtimes.is_empty() {
+        display_success("No runtimes are pinned, nothing to install");
+        return Ok(());
+    }
+
+    let lock = if locked {
+        let lock_path = LockFile::path_for(&config);
+        let lock = LockFile::load(&lock_path)?.ok_or_else(|| {
+            RvmError::FileNotFound(format!("{} (run `rvm lock` first)", lock_path.display()))
+        })?;
+        lock.check_matches(&config)?;
+        display_step(&format!("Installing exact versions from {}", LOCK_FILE));
+        Some(lock)
+    } else {
+        None
+    };
+
+    let mut failed = Vec::new();
+
+    for (runtime_name, version) in &config.runtimes {
+        let result = match &lock {
+            Some(lock) => install_locked_runtime(runtime_name, lock),
+            None => install_pinned_runtime(runtime_name, version),
+        };
+
+        if let Err(e) = result {
+            display_error(&format!("Failed to install {} {}: {}", runtime_name, version, e));
+            failed.push(runtime_name.clone());
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(RvmError::CommandExecutionFailed(format!(
+            "could not install {}",
+            failed.join(", ")
+        )));
+    }
+
+    display_success(&format!(
+        "All {} pinned runtimes are installed",
+        config.runtimes.len()
+    ));
+    Ok(())
+}
+
+/// Install a single pinned runtime if missing, then use it for the current session
+fn install_pinned_runtime(runtime_name: &str, version: &str) -> Result<()> {
+    let rt = runtime::get_runtime(runtime_name)?;
+
+    display_step(&format!("Checking {} {}", runtime_name, version));
+    let installed = match rt.find_installed(version)? {
+        Some(installed) => {
+            display_success(&format!(
+                "{} {} is already installed ({})",
+                runtime_name, version, installed
+            ));
+            installed
+        }
+        None => {
+            match rt.add(Some(version)) {
+                Ok(()) | Err(RvmError::VersionAlreadyInstalled(_)) => {}
+                Err(e) => return Err(e),
+            }
+
+            // Aliases a runtime can't match against installed versions resolve remotely
+            match rt.find_installed(version)? {
+                Some(installed) => installed,
+                None => {
+                    let resolved = rt.resolve_version(version)?;
+                    rt.find_installed(&resolved)?
+                        .ok_or_else(|| RvmError::VersionNotFound(resolved))?
+                }
+            }
+        }
+    };
+
+    rt.use_version(&installed)
+}
+
+/// Install the exact locked version of a runtime, then use it for the current session
+fn install_locked_runtime(runtime_name: &str, lock: &LockFile) -> Result<()> {
+    let rt = runtime::get_runtime(runtime_name)?;
+    let locked = lock
+        .runtimes
+        .get(runtime_name)
+        .ok_or_else(|| RvmError::LockfileMismatch(format!("{} is not locked", runtime_name)))?;
+
+    // Only the version is locked, the runtime verifies its own downloads
+    if !rt.lockable() {
+        return install_pinned_runtime(runtime_name, &locked.version);
+    }
+
+    // The artifact is looked up by URL, the variant it was recorded under may cover this one too
+    let platform = Platform::current()?;
+    let expected_url = rt.artifact_url(&locked.version, &platform)?;
+    let artifact = locked
+        .artifacts
+        .values()
+        .find(|artifact| artifact.url == expected_url)
+        .ok_or_else(|| {
+            RvmError::LockfileMismatch(format!(
+                "{} {} has no locked {} artifact ({})",
+                runtime_name,
+                locked.version,
+                platform.key(),
+                expected_url
+            ))
+        })?;
+
+    display_step(&format!("Checking {} {}", runtime_name, locked.version));
+    let installed = match rt.find_installed(&locked.version)? {
+        Some(installed) => {
+            display_success(&format!("{} {} is already installed", runtime_name, installed));
+            installed
+        }
+        None => {
+            match rt.add_locked(&locked.version, &artifact.sha256) {
+                Ok(()) | Err(RvmError::VersionAlreadyInstalled(_)) => {}
+                Err(e) => return Err(e),
+            }
+            rt.find_installed(&locked.version)?
+                .ok_or_else(|| RvmError::VersionNotFound(locked.version.clone()))?
+        }
+    };
+
+    rt.use_version(&installed)
+}
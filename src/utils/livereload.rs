@@ -0,0 +1,191 @@
+use super::error::{Result, RvmError};
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::time::interval;
+
+/// Comment sent to idle event streams so proxies and browsers keep them open
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+/// Changes to these files only need the page's stylesheets swapped
+const STYLESHEET_EXTENSIONS: [&str; 4] = ["css", "scss", "sass", "less"];
+
+/// Largest request head read before giving up on a connection
+const MAX_REQUEST: usize = 8192;
+
+/// Served at /livereload.js, listens for reload events from the server it was loaded from
+const SNIPPET: &str = r#"(function () {
+  var origin = new URL(document.currentScript.src).origin;
+  var source = new EventSource(origin + "/events");
+  source.addEventListener("reload", function () {
+    location.reload();
+  });
+  source.addEventListener("css", function () {
+    // Load the new stylesheet next to the old one, so the page never renders unstyled
+    document.querySelectorAll('link[rel="stylesheet"]').forEach(function (link) {
+      var url = new URL(link.href);
+      url.searchParams.set("livereload", Date.now());
+      var next = link.cloneNode();
+      next.href = url.toString();
+      next.onload = function () {
+        link.remove();
+      };
+      link.after(next);
+    });
+  });
+})();
+"#;
+
+/// What connected browsers are asked to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reload {
+    Page,
+    /// Swap the stylesheets without reloading the page
+    Css,
+}
+
+impl Reload {
+    /// Stylesheet-only changes are hot swapped, anything else reloads the page
+    pub fn for_changes<'a>(paths: impl IntoIterator<Item = &'a PathBuf>) -> Self {
+        let mut paths = paths.into_iter().peekable();
+        let stylesheets_only = paths.peek().is_some()
+            && paths.all(|path| {
+                path.extension()
+                    .is_some_and(|ext| STYLESHEET_EXTENSIONS.iter().any(|css| ext == *css))
+            });
+        if stylesheets_only { Reload::Css } else { Reload::Page }
+    }
+
+    fn event_name(self) -> &'static str {
+        match self {
+            Reload::Page => "reload",
+            Reload::Css => "css",
+        }
+    }
+}
+
+/// Local server pushing reload events to browsers over Server-Sent Events
+#[derive(Clone)]
+pub struct LiveReload {
+    reloads: broadcast::Sender<Reload>,
+    port: u16,
+}
+
+impl LiveReload {
+    /// Listen on localhost only, the server is meant for the developer's own browser
+    pub async fn start(port: u16) -> Result<Self> {
+        let listener = TcpListener::bind(("127.0.0.1", port)).await.map_err(|e| {
+            RvmError::CommandExecutionFailed(format!(
+                "failed to start the live reload server on port {}: {}",
+                port, e
+            ))
+        })?;
+
+        let (reloads, _) = broadcast::channel(16);
+        let server = LiveReload { reloads, port };
+
+        let accepting = server.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let reloads = accepting.reloads.subscribe();
+                tokio::spawn(handle_connection(stream, reloads));
+            }
+        });
+
+        Ok(server)
+    }
+
+    /// Script tag to add to the page
+    pub fn snippet_tag(&self) -> String {
+        format!(
+            "<script src=\"http://localhost:{}/livereload.js\"></script>",
+            self.port
+        )
+    }
+
+    /// Push a reload to every connected browser, returns how many got it
+    pub fn send(&self, reload: Reload) -> usize {
+        self.reloads.send(reload).unwrap_or(0)
+    }
+}
+
+/// Serve one request: the snippet, an event stream or a 404
+async fn handle_connection(mut stream: TcpStream, mut reloads: broadcast::Receiver<Reload>) {
+    let Some(path) = read_request_path(&mut stream).await else {
+        return;
+    };
+
+    match path.split('?').next().unwrap_or_default() {
+        "/livereload.js" => {
+            let _ = respond(&mut stream, "200 OK", "application/javascript", SNIPPET).await;
+        }
+        "/events" => {
+            let head = "HTTP/1.1 200 OK\r\n\
+                        Content-Type: text/event-stream\r\n\
+                        Cache-Control: no-cache\r\n\
+                        Connection: keep-alive\r\n\
+                        Access-Control-Allow-Origin: *\r\n\r\n";
+            if stream.write_all(head.as_bytes()).await.is_err() {
+                return;
+            }
+
+            let mut keep_alive = interval(KEEP_ALIVE);
+            loop {
+                let message = tokio::select! {
+                    reload = reloads.recv() => match reload {
+                        Ok(reload) => format!("event: {}\ndata: {{}}\n\n", reload.event_name()),
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => return,
+                    },
+                    _ = keep_alive.tick() => ": keep-alive\n\n".to_string(),
+                };
+                // The browser went away
+                if stream.write_all(message.as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+        }
+        _ => {
+            let _ = respond(&mut stream, "404 Not Found", "text/plain", "Not found\n").await;
+        }
+    }
+}
+
+/// Read the request head and return the path of a GET request
+async fn read_request_path(stream: &mut TcpStream) -> Option<String> {
+    let mut head = Vec::new();
+    let mut buffer = [0; 1024];
+    while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut buffer).await.ok()?;
+        if read == 0 || head.len() > MAX_REQUEST {
+            return None;
+        }
+        head.extend_from_slice(&buffer[..read]);
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next()?.split_whitespace();
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some(path)) => Some(path.to_string()),
+        _ => None,
+    }
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> std::io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+         Access-Control-Allow-Origin: *\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await
+}
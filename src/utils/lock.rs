@@ -0,0 +1,185 @@
+use super::download::{Platform, download_with_progress, is_published, sha256_hex};
+use super::error::{Result, RvmError};
+use super::project::ProjectConfig;
+use super::ui::{display_step, display_success};
+use crate::runtime;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Lockfile written next to the project pin file
+pub const LOCK_FILE: &str = "rvm.lock";
+
+const LOCK_FILE_HEADER: &str =
+    "# This file is generated by `rvm lock`. It is not intended for manual editing.\n\n";
+
+/// Exact versions and artifacts resolved from the project pins
+#[derive(Serialize, Deserialize, Default)]
+pub struct LockFile {
+    #[serde(default)]
+    pub runtimes: BTreeMap<String, LockedRuntime>,
+}
+
+/// A pinned runtime resolved to an exact version (e.g. node "20" -> v20.11.0)
+#[derive(Serialize, Deserialize, Clone)]
+pub struct LockedRuntime {
+    pub pin: String,
+    pub version: String,
+    /// Download artifacts keyed by build variant (x64, x64-musl, arm64, ...). A build shared by
+    /// several variants is recorded once, under the first of them
+    pub artifacts: BTreeMap<String, LockedArtifact>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct LockedArtifact {
+    pub url: String,
+    pub sha256: String,
+}
+
+impl LockFile {
+    /// Path of the lockfile belonging to a project
+    pub fn path_for(config: &ProjectConfig) -> PathBuf {
+        config.root().join(LOCK_FILE)
+    }
+
+    /// Load a lockfile, returns None if it does not exist
+    pub fn load(path: &Path) -> Result<Option<LockFile>> {
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(path)?;
+        let lock = toml::from_str(&content).map_err(|e| {
+            RvmError::ConfigurationError(format!("{}: {}", path.display(), e))
+        })?;
+        Ok(Some(lock))
+    }
+
+    /// Write the lockfile to disk
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| RvmError::InternalError(format!("Failed to serialize lockfile: {}", e)))?;
+        std::fs::write(path, format!("{}{}", LOCK_FILE_HEADER, content))?;
+        Ok(())
+    }
+
+    /// Check that the lockfile covers exactly the project pins
+    pub fn check_matches(&self, config: &ProjectConfig) -> Result<()> {
+        for (runtime_name, pin) in &config.runtimes {
+            match self.runtimes.get(runtime_name) {
+                None => {
+                    return Err(RvmError::LockfileMismatch(format!(
+                        "{} is pinned but not locked",
+                        runtime_name
+                    )));
+                }
+                Some(locked) if locked.pin != *pin => {
+                    return Err(RvmError::LockfileMismatch(format!(
+                        "{} is pinned to {} but locked for {}",
+                        runtime_name, pin, locked.pin
+                    )));
+                }
+                Some(_) => {}
+            }
+        }
+
+        if let Some(runtime_name) = self
+            .runtimes
+            .keys()
+            .find(|name| !config.runtimes.contains_key(*name))
+        {
+            return Err(RvmError::LockfileMismatch(format!(
+                "{} is locked but no longer pinned",
+                runtime_name
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// Resolve every project pin to an exact version and record its artifacts in rvm.lock
+pub fn handle_lock_command() -> Result<()> {
+    let config = ProjectConfig::find()?.ok_or_else(|| {
+        RvmError::FileNotFound(
+            "no rvm.toml or .tool-versions found in this directory or its parents".to_string(),
+        )
+    })?;
+
+    let lock_path = LockFile::path_for(&config);
+    let previous = LockFile::load(&lock_path)?.unwrap_or_default();
+    let mut lock = LockFile::default();
+
+    for (runtime_name, pin) in &config.runtimes {
+        let rt = runtime::get_runtime(runtime_name)?;
+
+        display_step(&format!("Resolving {} {}", runtime_name, pin));
+        let version = rt.resolve_version(pin)?;
+        display_success(&format!("Locked {} {} to {}", runtime_name, pin, version));
+
+        let mut artifacts: BTreeMap<String, LockedArtifact> = BTreeMap::new();
+        let platforms = if rt.lockable() {
+            Platform::all()
+        } else {
+            display_step(&format!(
+                "{} verifies its own downloads, only its version is locked",
+                runtime_name
+            ));
+            Vec::new()
+        };
+        for platform in platforms {
+            let url = rt.artifact_url(&version, &platform)?;
+            if artifacts.values().any(|artifact| artifact.url == url) {
+                continue;
+            }
+
+            // Prefer the distributor's checksum, otherwise reuse the recorded one when the artifact
+            // hasn't changed
+            let recorded = previous
+                .runtimes
+                .get(runtime_name)
+                .and_then(|locked| locked.artifacts.values().find(|artifact| artifact.url == url))
+                .map(|artifact| artifact.sha256.clone());
+
+            let sha256 = match rt.published_sha256(&version, &platform)?.or(recorded) {
+                Some(sha256) => sha256,
+                // Without a published checksum, the first download is trusted
+                None => {
+                    if !is_published(&url)? {
+                        display_step(&format!(
+                            "{} {} isn't published for {}, skipping it",
+                            runtime_name,
+                            version,
+                            platform.key()
+                        ));
+                        continue;
+                    }
+                    display_step(&format!(
+                        "Computing checksum for {} ({})",
+                        runtime_name,
+                        platform.key()
+                    ));
+                    sha256_hex(&download_with_progress(&url)?)
+                }
+            };
+            artifacts.insert(platform.key(), LockedArtifact { url, sha256 });
+        }
+
+        lock.runtimes.insert(
+            runtime_name.clone(),
+            LockedRuntime {
+                pin: pin.clone(),
+                version,
+                artifacts,
+            },
+        );
+    }
+
+    lock.save(&lock_path)?;
+    display_success(&format!(
+        "Wrote {} locked runtimes to {}",
+        lock.runtimes.len(),
+        lock_path.display()
+    ));
+    Ok(())
+}
@@ -1,8 +1,19 @@
 pub mod cli;
 pub mod download;
 pub mod error; 
+pub mod install;
 pub mod list;
+pub mod livereload;
+pub mod lock;
 pub mod profile;
+pub mod project;
+pub mod semver;
 pub mod ui;
 pub mod version;
+pub mod watch_env;
+pub mod watch_events;
+pub mod watch_filter;
+pub mod watch_keys;
+pub mod watch_ready;
+pub mod watch_tasks;
 pub mod watcher;
\ No newline at end of file
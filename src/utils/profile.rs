@@ -69,6 +69,28 @@ pub fn add_runtime_to_path(runtime_name: &str, version: &str) -> Result<()> {
     Ok(())
 }
 
+/// Add environment variables a runtime needs to .profile (e.g. RUSTUP_HOME). Values must live
+/// under the runtime's home so remove_all_runtime_paths clears them with the PATH entries
+pub fn add_runtime_env(runtime_name: &str, vars: &[(&str, String)]) -> Result<()> {
+    let profile_path = get_profile_file()?;
+
+    let mut env_lines = format!("\n# Added by rvm for {} environment\n", runtime_name);
+    for (key, value) in vars {
+        env_lines.push_str(&format!("export {}=\"{}\"\n", key, value));
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&profile_path)?;
+
+    file.write_all(env_lines.as_bytes())?;
+
+    println!("Added {} environment to ~/.profile", runtime_name);
+
+    Ok(())
+}
+
 /// Remove specific runtime version from PATH in .profile
 pub fn remove_runtime_from_path(runtime_name: &str, version: &str) -> Result<()> {
     display_step(&format!("Removing {} {} from PATH", runtime_name, version));
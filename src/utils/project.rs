@@ -0,0 +1,191 @@
+use crate::runtime::Runtime;
+use crate::utils::error::{Result, RvmError};
+use crate::utils::ui::display_step;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Project pin file written by rvm
+pub const PROJECT_FILE: &str = "rvm.toml";
+
+/// asdf-compatible pin file, read when no rvm.toml is present
+pub const TOOL_VERSIONS_FILE: &str = ".tool-versions";
+
+/// A version requested by a project file (e.g. "lts/iron" from .nvmrc)
+#[derive(Debug, Clone)]
+pub struct VersionHint {
+    pub version: String,
+    pub source: PathBuf,
+}
+
+/// Runtimes pinned by a project, keyed by rvm runtime name (e.g. node = "20"), and its watch
+/// tasks keyed by task name
+#[derive(Debug, Clone)]
+pub struct ProjectConfig {
+    pub file: PathBuf,
+    pub runtimes: BTreeMap<String, String>,
+    pub watch: BTreeMap<String, WatchTask>,
+}
+
+/// A `[watch.<name>]` task run by `rvm watch` without a command. Paths are relative to the
+/// project root
+#[derive(Debug, Clone, Deserialize)]
+pub struct WatchTask {
+    pub command: String,
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    #[serde(default)]
+    pub ext: Vec<String>,
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Shell command line that has to succeed before the command is (re)started
+    #[serde(default)]
+    pub build: Option<String>,
+    /// Readiness checks, at most one: a localhost port, an output regex or a URL answering 2xx
+    #[serde(default)]
+    pub ready_port: Option<u16>,
+    #[serde(default)]
+    pub ready_log: Option<String>,
+    #[serde(default)]
+    pub ready_url: Option<String>,
+    /// Build step that runs to completion on each change, rather than a long-running service
+    #[serde(default)]
+    pub oneshot: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct RvmToml {
+    #[serde(default)]
+    runtimes: BTreeMap<String, String>,
+    #[serde(default)]
+    watch: BTreeMap<String, WatchTask>,
+}
+
+impl ProjectConfig {
+    /// Directory containing the pin file
+    pub fn root(&self) -> &Path {
+        self.file.parent().unwrap_or(Path::new("."))
+    }
+
+    /// Find the nearest pin file, starting at the current directory and walking up
+    pub fn find() -> Result<Option<ProjectConfig>> {
+        let current_dir = std::env::current_dir()?;
+        Self::find_from(&current_dir)
+    }
+
+    /// Find the nearest pin file, starting at `start` and walking up
+    pub fn find_from(start: &Path) -> Result<Option<ProjectConfig>> {
+        for dir in start.ancestors() {
+            let rvm_toml = dir.join(PROJECT_FILE);
+            if rvm_toml.is_file() {
+                return Self::load_rvm_toml(&rvm_toml).map(Some);
+            }
+
+            let tool_versions = dir.join(TOOL_VERSIONS_FILE);
+            if tool_versions.is_file() {
+                return Self::load_tool_versions(&tool_versions).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Load an rvm.toml file
+    fn load_rvm_toml(path: &Path) -> Result<ProjectConfig> {
+        let content = std::fs::read_to_string(path)?;
+        let parsed: RvmToml = toml::from_str(&content).map_err(|e| {
+            RvmError::ConfigurationError(format!("{}: {}", path.display(), e))
+        })?;
+
+        let runtimes = parsed
+            .runtimes
+            .into_iter()
+            .map(|(name, version)| (normalize_runtime_name(&name), version))
+            .collect();
+
+        Ok(ProjectConfig {
+            file: path.to_path_buf(),
+            runtimes,
+            watch: parsed.watch,
+        })
+    }
+
+    /// Load an asdf-style .tool-versions file (`<tool> <version> [fallback...]` per line)
+    fn load_tool_versions(path: &Path) -> Result<ProjectConfig> {
+        let content = std::fs::read_to_string(path)?;
+        let mut runtimes = BTreeMap::new();
+
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let mut parts = line.split_whitespace();
+            if let (Some(tool), Some(version)) = (parts.next(), parts.next()) {
+                // Only the first version is used, asdf fallbacks are ignored
+                runtimes.insert(normalize_runtime_name(tool), version.to_string());
+            }
+        }
+
+        Ok(ProjectConfig {
+            file: path.to_path_buf(),
+            runtimes,
+            watch: BTreeMap::new(),
+        })
+    }
+
+    /// Version pinned for a runtime, if any
+    pub fn pinned_version(&self, runtime_name: &str) -> Option<VersionHint> {
+        self.runtimes.get(runtime_name).map(|version| VersionHint {
+            version: version.clone(),
+            source: self.file.clone(),
+        })
+    }
+}
+
+/// Work out which version a command should act on when none was given on the command line.
+/// The rvm.toml/.tool-versions pin wins over runtime specific files such as .nvmrc
+pub fn requested_version(
+    runtime_name: &str,
+    rt: &dyn Runtime,
+    version: Option<String>,
+) -> Result<Option<String>> {
+    if version.is_some() {
+        return Ok(version);
+    }
+
+    let pinned = ProjectConfig::find()?.and_then(|config| config.pinned_version(runtime_name));
+    let hint = match pinned {
+        Some(hint) => Some(hint),
+        None => rt.project_version(&std::env::current_dir()?)?,
+    };
+
+    Ok(hint.map(|hint| {
+        display_step(&format!(
+            "Using {} {} from {}",
+            runtime_name,
+            hint.version,
+            hint.source.display()
+        ));
+        hint.version
+    }))
+}
+
+/// Read the first non-empty, non-comment line of a version file such as .nvmrc
+pub fn read_version_file(path: &Path) -> Result<Option<String>> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .find(|line| !line.is_empty())
+        .map(|line| line.to_string()))
+}
+
+/// Map asdf plugin names onto rvm runtime names (e.g. nodejs -> node, golang -> go)
+fn normalize_runtime_name(name: &str) -> String {
+    match name {
+        "nodejs" => "node".to_string(),
+        "golang" => "go".to_string(),
+        "cpython" => "python".to_string(),
+        "tailwind" => "tailwindcss".to_string(),
+        other => other.to_string(),
+    }
+}
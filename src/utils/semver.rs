@@ -0,0 +1,477 @@
+use std::cmp::Ordering;
+
+/// A parsed runtime version. Accepts "v"-prefixed versions ("v20.11.0"), Go release names
+/// ("go1.22.5", "go1.22rc1") and semver prereleases ("v4.0.0-beta.8")
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+}
+
+/// A prerelease identifier, e.g. "beta" and 8 in "4.0.0-beta.8" or "rc" and 1 in "go1.22rc1"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Identifier {
+    Numeric(u64),
+    Alpha(String),
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+        }
+    }
+
+    /// Parse a full or partial version, missing components default to 0
+    pub fn parse(input: &str) -> Option<Version> {
+        let partial = Partial::parse(input)?;
+        partial.major?;
+        Some(partial.floor())
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    fn same_release(&self, other: &Version) -> bool {
+        (self.major, self.minor, self.patch) == (other.major, other.minor, other.patch)
+    }
+}
+
+impl Ord for Identifier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Numeric identifiers always have lower precedence than alphanumeric ones
+        match (self, other) {
+            (Identifier::Numeric(a), Identifier::Numeric(b)) => a.cmp(b),
+            (Identifier::Numeric(_), Identifier::Alpha(_)) => Ordering::Less,
+            (Identifier::Alpha(_), Identifier::Numeric(_)) => Ordering::Greater,
+            (Identifier::Alpha(a), Identifier::Alpha(b)) => a.cmp(b),
+        }
+    }
+}
+
+impl PartialOrd for Identifier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                // A prerelease comes before its release (1.22rc1 < 1.22.0)
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Split a prerelease tag into identifiers ("beta.8" -> [beta, 8], "rc1" -> [rc, 1])
+fn parse_prerelease(input: &str) -> Vec<Identifier> {
+    let mut identifiers = Vec::new();
+    for part in input.split('.').filter(|part| !part.is_empty()) {
+        // Go style tags run the label and number together (rc1, beta2)
+        let digits_at = part
+            .find(|c: char| c.is_ascii_digit())
+            .filter(|&i| i > 0 && part[i..].chars().all(|c| c.is_ascii_digit()));
+
+        match (part.parse::<u64>(), digits_at) {
+            (Ok(number), _) => identifiers.push(Identifier::Numeric(number)),
+            (Err(_), Some(i)) => {
+                identifiers.push(Identifier::Alpha(part[..i].to_string()));
+                identifiers.push(Identifier::Numeric(part[i..].parse().unwrap_or(0)));
+            }
+            (Err(_), None) => identifiers.push(Identifier::Alpha(part.to_string())),
+        }
+    }
+    identifiers
+}
+
+/// A version with optional (or wildcard) minor and patch components, e.g. "18", "1.21.x"
+#[derive(Debug, Clone)]
+struct Partial {
+    major: Option<u64>,
+    minor: Option<u64>,
+    patch: Option<u64>,
+    pre: Vec<Identifier>,
+}
+
+impl Partial {
+    fn parse(input: &str) -> Option<Partial> {
+        let input = input.trim();
+        let input = input.strip_prefix('=').unwrap_or(input);
+        let input = input
+            .strip_prefix("go")
+            .or_else(|| input.strip_prefix('v'))
+            .unwrap_or(input);
+
+        // Build metadata is not part of the version's precedence
+        let input = input.split('+').next().unwrap_or(input);
+
+        let (release, mut pre) = match input.split_once('-') {
+            Some((release, pre)) => (release, parse_prerelease(pre)),
+            None => (input, Vec::new()),
+        };
+
+        let mut components = [None; 3];
+        let mut parts = release.split('.');
+        for component in components.iter_mut() {
+            let Some(part) = parts.next() else { break };
+            if matches!(part, "x" | "X" | "*") {
+                break;
+            }
+
+            // Go names put the prerelease straight after the last component (1.22rc1)
+            let digits_end = part
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(part.len());
+            *component = Some(part[..digits_end].parse().ok()?);
+            if digits_end < part.len() {
+                if !pre.is_empty() {
+                    return None;
+                }
+                pre = parse_prerelease(&part[digits_end..]);
+                break;
+            }
+        }
+
+        if parts.next().is_some_and(|extra| !extra.is_empty()) {
+            return None;
+        }
+
+        Some(Partial {
+            major: components[0],
+            minor: components[1],
+            patch: components[2],
+            pre,
+        })
+    }
+
+    /// Whether the partial names a single version (all components or a prerelease given)
+    fn is_exact(&self) -> bool {
+        self.patch.is_some() || !self.pre.is_empty()
+    }
+
+    /// Smallest version covered by the partial (e.g. "1.2" -> 1.2.0)
+    fn floor(&self) -> Version {
+        Version {
+            major: self.major.unwrap_or(0),
+            minor: self.minor.unwrap_or(0),
+            patch: self.patch.unwrap_or(0),
+            pre: self.pre.clone(),
+        }
+    }
+
+    /// First version above the partial (e.g. "1.2" -> 1.3.0), None if it covers everything
+    fn ceiling(&self) -> Option<Version> {
+        match (self.major, self.minor, self.patch) {
+            (None, _, _) => None,
+            (Some(major), None, _) => Some(Version::new(major + 1, 0, 0)),
+            (Some(major), Some(minor), None) => Some(Version::new(major, minor + 1, 0)),
+            (Some(major), Some(minor), Some(patch)) => Some(Version::new(major, minor, patch + 1)),
+        }
+    }
+}
+
+// Ranges are normalised to exact matches or half-open bounds, e.g. "<=1.2" becomes "<1.3.0"
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Exact,
+    GreaterEq,
+    Less,
+}
+
+#[derive(Debug, Clone)]
+struct Comparator {
+    op: Op,
+    version: Version,
+}
+
+impl Comparator {
+    fn matches(&self, version: &Version) -> bool {
+        let ordering = version.cmp(&self.version);
+        match self.op {
+            Op::Exact => ordering == Ordering::Equal,
+            Op::GreaterEq => ordering != Ordering::Less,
+            Op::Less => ordering == Ordering::Less,
+        }
+    }
+}
+
+/// A version requirement. Accepts exact versions ("1.22.5", "go1.22rc1"), partial versions
+/// ("18", "1.21") and npm style ranges ("^18", ">=1.21 <1.23", "~3.4", "18.x", "16 || 18")
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+    // Any of the comparator sets may match, all comparators within a set must match
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    pub fn parse(input: &str) -> Option<VersionReq> {
+        let mut alternatives = Vec::new();
+        for alternative in input.split("||") {
+            alternatives.push(Self::parse_comparator_set(alternative)?);
+        }
+        Some(VersionReq { alternatives })
+    }
+
+    /// Prereleases only match when a comparator names a prerelease of the same release, so
+    /// "^4" never selects 4.1.0-beta.1 but ">=4.0.0-beta.1" does
+    pub fn matches(&self, version: &Version) -> bool {
+        self.alternatives.iter().any(|set| {
+            set.iter().all(|comparator| comparator.matches(version))
+                && (!version.is_prerelease()
+                    || set.iter().any(|comparator| {
+                        comparator.version.is_prerelease()
+                            && comparator.version.same_release(version)
+                    }))
+        })
+    }
+
+    fn parse_comparator_set(input: &str) -> Option<Vec<Comparator>> {
+        let tokens = Self::tokenize(input);
+
+        // Hyphen range: "1.2.3 - 2.3.4"
+        if tokens.len() == 3 && tokens[1] == "-" {
+            let lower = Partial::parse(&tokens[0])?;
+            let upper = Partial::parse(&tokens[2])?;
+            return Some(Self::bounded(lower.floor(), upper.ceiling()));
+        }
+
+        let mut set = Vec::new();
+        for token in &tokens {
+            set.extend(Self::parse_comparator(token)?);
+        }
+        Some(set)
+    }
+
+    /// Split a comparator set on whitespace, joining operators separated from their version
+    fn tokenize(input: &str) -> Vec<String> {
+        let mut tokens: Vec<String> = Vec::new();
+        let mut pending_op = String::new();
+
+        for word in input.split_whitespace() {
+            if word != "-" && word.chars().all(|c| matches!(c, '<' | '>' | '=' | '^' | '~')) {
+                pending_op.push_str(word);
+                continue;
+            }
+            tokens.push(format!("{}{}", pending_op, word));
+            pending_op.clear();
+        }
+
+        tokens
+    }
+
+    fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+        let comparator = |op, version| Comparator { op, version };
+
+        if let Some(rest) = token.strip_prefix(">=") {
+            let partial = Partial::parse(rest)?;
+            return Some(vec![comparator(Op::GreaterEq, partial.floor())]);
+        }
+        if let Some(rest) = token.strip_prefix("<=") {
+            let partial = Partial::parse(rest)?;
+            return Some(match partial.ceiling() {
+                Some(ceiling) => vec![comparator(Op::Less, ceiling)],
+                None => Vec::new(),
+            });
+        }
+        if let Some(rest) = token.strip_prefix('>') {
+            let partial = Partial::parse(rest)?;
+            return Some(match partial.ceiling() {
+                Some(ceiling) => vec![comparator(Op::GreaterEq, ceiling)],
+                // ">*" can never be satisfied
+                None => vec![comparator(Op::Less, Version::new(0, 0, 0))],
+            });
+        }
+        if let Some(rest) = token.strip_prefix('<') {
+            let partial = Partial::parse(rest)?;
+            return Some(vec![comparator(Op::Less, partial.floor())]);
+        }
+        if let Some(rest) = token.strip_prefix('^') {
+            let partial = Partial::parse(rest)?;
+            let floor = partial.floor();
+            // Caret allows changes that don't modify the left-most non-zero component
+            let ceiling = match (partial.major, partial.minor, partial.patch) {
+                (Some(0), Some(0), Some(patch)) => Some(Version::new(0, 0, patch + 1)),
+                (Some(0), Some(minor), _) => Some(Version::new(0, minor + 1, 0)),
+                (Some(major), _, _) => Some(Version::new(major + 1, 0, 0)),
+                (None, _, _) => None,
+            };
+            return Some(Self::bounded(floor, ceiling));
+        }
+        if let Some(rest) = token.strip_prefix('~') {
+            let rest = rest.strip_prefix('>').unwrap_or(rest);
+            let partial = Partial::parse(rest)?;
+            // Tilde allows patch changes when a minor version is given, minor changes otherwise
+            let ceiling = match (partial.major, partial.minor) {
+                (Some(major), Some(minor)) => Some(Version::new(major, minor + 1, 0)),
+                (Some(major), None) => Some(Version::new(major + 1, 0, 0)),
+                (None, _) => None,
+            };
+            return Some(Self::bounded(partial.floor(), ceiling));
+        }
+
+        // Exact ("=3.4.1", "go1.22rc1") or partial ("18", "1.21.x") version
+        let partial = Partial::parse(token)?;
+        if partial.is_exact() {
+            return Some(vec![comparator(Op::Exact, partial.floor())]);
+        }
+        Some(Self::bounded(partial.floor(), partial.ceiling()))
+    }
+
+    fn bounded(floor: Version, ceiling: Option<Version>) -> Vec<Comparator> {
+        let mut set = vec![Comparator {
+            op: Op::GreaterEq,
+            version: floor,
+        }];
+        if let Some(ceiling) = ceiling {
+            set.push(Comparator {
+                op: Op::Less,
+                version: ceiling,
+            });
+        }
+        set
+    }
+}
+
+/// Pick the newest version name matching a version or range input (e.g. "18" picks "v18.20.0"
+/// out of ["v20.11.0", "v18.20.0", "v18.19.1"]). Names that don't parse as versions are skipped
+pub fn select_newest<'a, I>(candidates: I, version_input: &str) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let req = VersionReq::parse(version_input)?;
+    candidates
+        .into_iter()
+        .filter_map(|name| Version::parse(name).map(|version| (version, name)))
+        .filter(|(version, _)| req.matches(version))
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, name)| name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(input: &str) -> Version {
+        Version::parse(input).unwrap()
+    }
+
+    fn matches(range: &str, input: &str) -> bool {
+        VersionReq::parse(range).unwrap().matches(&version(input))
+    }
+
+    #[test]
+    fn parses_go_release_candidates() {
+        let rc = version("go1.21rc1");
+        assert_eq!((rc.major, rc.minor, rc.patch), (1, 21, 0));
+        assert_eq!(rc.pre, vec![Identifier::Alpha("rc".to_string()), Identifier::Numeric(1)]);
+
+        assert!(version("go1.20.14") < version("go1.21rc1"));
+        assert!(version("go1.21rc1") < version("go1.21rc2"));
+        assert!(version("go1.21rc2") < version("go1.21.0"));
+        assert!(version("go1.21beta1") < version("go1.21rc1"));
+    }
+
+    #[test]
+    fn strips_go_and_v_prefixes() {
+        assert_eq!(version("go1.22.5"), Version::new(1, 22, 5));
+        assert_eq!(version("v20.11.0"), Version::new(20, 11, 0));
+        assert_eq!(version("1.22"), Version::new(1, 22, 0));
+        assert_eq!(version("go1.22.5"), version("v1.22.5"));
+        assert!(Version::parse("canary").is_none());
+    }
+
+    #[test]
+    fn orders_semver_prereleases() {
+        assert!(version("v4.0.0-alpha.1") < version("v4.0.0-beta.8"));
+        assert!(version("v4.0.0-beta.8") < version("v4.0.0-beta.10"));
+        assert!(version("v4.0.0-beta.10") < version("v4.0.0"));
+        assert!(version("v3.4.17") < version("v4.0.0-alpha.1"));
+    }
+
+    #[test]
+    fn caret_and_tilde_on_zero_major() {
+        assert!(matches("^0.2.3", "0.2.9"));
+        assert!(!matches("^0.2.3", "0.3.0"));
+        assert!(matches("^0.0.3", "0.0.3"));
+        assert!(!matches("^0.0.3", "0.0.4"));
+        assert!(matches("^0.2", "0.2.0"));
+        assert!(!matches("^0.2", "0.3.0"));
+
+        assert!(matches("~0.2.3", "0.2.9"));
+        assert!(!matches("~0.2.3", "0.3.0"));
+        assert!(matches("~0", "0.9.0"));
+        assert!(!matches("~0", "1.0.0"));
+    }
+
+    #[test]
+    fn caret_tilde_and_x_ranges() {
+        assert!(matches("^18", "v18.20.4"));
+        assert!(!matches("^18", "v19.0.0"));
+        assert!(matches("~3.4", "v3.4.17"));
+        assert!(!matches("~3.4", "v3.5.0"));
+        assert!(matches("18.x", "v18.0.0"));
+        assert!(!matches("18.x", "v17.9.9"));
+        assert!(matches(">=1.21 <1.23", "go1.22.5"));
+        assert!(!matches(">=1.21 <1.23", "go1.23.0"));
+        assert!(matches(">= 18", "v20.0.0"));
+    }
+
+    #[test]
+    fn hyphen_ranges() {
+        assert!(matches("1.2.3 - 2.3.4", "1.2.3"));
+        assert!(matches("1.2.3 - 2.3.4", "2.3.4"));
+        assert!(!matches("1.2.3 - 2.3.4", "2.3.5"));
+        // A partial upper bound includes its whole minor
+        assert!(matches("1.2 - 2.3", "2.3.9"));
+        assert!(!matches("1.2 - 2.3", "2.4.0"));
+        assert!(!matches("1.2 - 2.3", "1.1.9"));
+    }
+
+    #[test]
+    fn alternatives() {
+        assert!(matches("16 || 18", "v16.20.2"));
+        assert!(matches("16 || 18", "v18.20.4"));
+        assert!(!matches("16 || 18", "v17.9.1"));
+        assert!(matches("^14.17 || >=16", "v20.0.0"));
+    }
+
+    #[test]
+    fn prereleases_only_match_their_own_release() {
+        // Partial versions and ranges skip prereleases unless they name one
+        assert!(!matches("1.22", "go1.22rc1"));
+        assert!(!matches(">=1.21", "go1.22rc1"));
+        assert!(!matches("^4", "v4.0.0-beta.8"));
+
+        assert!(matches(">=4.0.0-beta.2", "v4.0.0-beta.8"));
+        assert!(!matches(">=4.0.0-beta.2", "v4.1.0-beta.1"));
+        assert!(matches(">=4.0.0-beta.2", "v4.1.0"));
+        assert!(matches("go1.22rc1", "go1.22rc1"));
+        assert!(!matches("go1.22rc1", "go1.22rc2"));
+    }
+
+    #[test]
+    fn select_newest_skips_unparsable_names() {
+        let candidates = ["v20.11.0", "v18.19.1", "v18.20.0", "canary-abc", "v18.21.0-rc.1"];
+        assert_eq!(select_newest(candidates, "18"), Some("v18.20.0"));
+        assert_eq!(select_newest(candidates, "*"), Some("v20.11.0"));
+        assert_eq!(select_newest(candidates, "^21"), None);
+    }
+}
@@ -1,4 +1,5 @@
 use crate::utils::error::{Result, RvmError};
+use crate::utils::semver::{Version, select_newest};
 use crate::utils::ui::{display_error, display_step, display_success};
 use std::collections::BTreeMap;
 use std::os::unix::fs::PermissionsExt;
@@ -98,21 +99,21 @@ pub fn list_installed_versions(runtime_name: &str) -> Result<Vec<String>> {
             let entry = entry?;
             let path = entry.path();
 
-            if path.is_dir() {
-                if let Some(version_name) = path.file_name() {
-                    let version_str = version_name.to_string_lossy().to_string();
-                    // Verify it's a valid installation by checking for expected structure
-                    let bin_dir = path.join("bin");
-                    if bin_dir.exists() {
-                        installed_versions.push(version_str);
-                    }
+            if path.is_dir()
+                && let Some(version_name) = path.file_name()
+            {
+                let version_str = version_name.to_string_lossy().to_string();
+                // Verify it's a valid installation by checking for expected structure
+                let bin_dir = path.join("bin");
+                if bin_dir.exists() {
+                    installed_versions.push(version_str);
                 }
             }
         }
     }
 
     // Sort versions (newest first)
-    installed_versions.sort_by(|a, b| b.cmp(a));
+    installed_versions.sort_by(|a, b| compare_versions(b, a));
     Ok(installed_versions)
 }
 
@@ -127,47 +128,8 @@ pub fn resolve_installed_version(runtime_name: &str, version_input: &str) -> Res
         )));
     }
 
-    // Try exact match first
-    if installed_versions.contains(&version_input.to_string()) {
-        return Ok(version_input.to_string());
-    }
-
-    // Try with 'v' prefix
-    let v_prefixed = if version_input.starts_with('v') {
-        version_input.to_string()
-    } else {
-        format!("v{}", version_input)
-    };
-
-    if installed_versions.contains(&v_prefixed) {
-        return Ok(v_prefixed);
-    }
-
-    // Try major version match (e.g., "18" matches "v18.20.0")
-    if version_input.chars().all(|c| c.is_ascii_digit()) {
-        let target_major = format!("v{}", version_input);
-        for installed in &installed_versions {
-            if installed.starts_with(&target_major)
-                && installed.chars().nth(target_major.len()) == Some('.')
-            {
-                return Ok(installed.clone());
-            }
-        }
-    }
-
-    // Try major.minor match (e.g., "18.20" matches "v18.20.0")
-    if version_input.matches('.').count() == 1 {
-        let target_prefix = if version_input.starts_with('v') {
-            version_input.to_string()
-        } else {
-            format!("v{}", version_input)
-        };
-
-        for installed in &installed_versions {
-            if installed.starts_with(&target_prefix) {
-                return Ok(installed.clone());
-            }
-        }
+    if let Some(installed) = match_installed_version(&installed_versions, version_input) {
+        return Ok(installed);
     }
 
     // If no match found, show available versions
@@ -184,6 +146,26 @@ pub fn resolve_installed_version(runtime_name: &str, version_input: &str) -> Res
     Err(RvmError::VersionNotFound(version_input.to_string()))
 }
 
+/// Find an installed version matching the input without reporting anything, returns None if
+/// nothing matches (e.g., "18" -> Some("v18.20.0"))
+pub fn find_installed_version(runtime_name: &str, version_input: &str) -> Result<Option<String>> {
+    let installed_versions = list_installed_versions(runtime_name)?;
+    Ok(match_installed_version(&installed_versions, version_input))
+}
+
+/// Match version input against a list of installed versions. Accepts exact versions, partial
+/// versions ("18", "1.21"), ranges ("^18", ">=1.21 <1.23") and "latest" for the newest stable one
+fn match_installed_version(installed_versions: &[String], version_input: &str) -> Option<String> {
+    // Try exact directory name first (e.g. "v18.20.0")
+    if installed_versions.iter().any(|v| v == version_input) {
+        return Some(version_input.to_string());
+    }
+
+    let version_input = if version_input == "latest" { "*" } else { version_input };
+    select_newest(installed_versions.iter().map(String::as_str), version_input)
+        .map(|installed| installed.to_string())
+}
+
 /// Apply a runtime version to the current session for any runtime
 pub fn apply_version_to_current_session(
     runtime_name: &str,
@@ -301,24 +283,15 @@ pub fn detect_channel(version: &str, is_lts: bool) -> String {
     }
 }
 
-/// Compare two version strings for sorting (e.g., "v20.11.0" vs "v20.10.5")
+/// Compare two version strings for sorting (e.g., "v20.11.0" vs "v20.10.5", "go1.22rc1" vs
+/// "go1.22.0"). Strings that don't parse as versions sort before those that do
 pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
-    let clean_a = a.strip_prefix('v').unwrap_or(a);
-    let clean_b = b.strip_prefix('v').unwrap_or(b);
-
-    let parts_a: Vec<u32> = clean_a.split('.').filter_map(|s| s.parse().ok()).collect();
-    let parts_b: Vec<u32> = clean_b.split('.').filter_map(|s| s.parse().ok()).collect();
-
-    // Compare major, minor, patch in order
-    for (part_a, part_b) in parts_a.iter().zip(parts_b.iter()) {
-        match part_a.cmp(part_b) {
-            std::cmp::Ordering::Equal => continue,
-            other => return other,
-        }
+    match (Version::parse(a), Version::parse(b)) {
+        (Some(version_a), Some(version_b)) => version_a.cmp(&version_b).then_with(|| a.cmp(b)),
+        (Some(_), None) => std::cmp::Ordering::Greater,
+        (None, Some(_)) => std::cmp::Ordering::Less,
+        (None, None) => a.cmp(b),
     }
-
-    // If all compared parts are equal, the longer version is considered greater
-    parts_a.len().cmp(&parts_b.len())
 }
 
 /// Format a list of versions with smart line wrapping and truncation
@@ -375,11 +348,11 @@ pub fn group_versions_by_channel(version_infos: Vec<VersionInfo>) -> Vec<String>
         if let Some(major) = extract_major_version(&version_info.version) {
             channel_groups
                 .entry(version_info.channel.clone())
-                .or_insert_with(BTreeMap::new)
+                .or_default()
                 .entry(major)
-                .or_insert_with(BTreeMap::new)
+                .or_default()
                 .entry(version_info.major_minor)
-                .or_insert_with(Vec::new)
+                .or_default()
                 .push(clean_version_for_display(&version_info.version));
         }
     }
@@ -524,3 +497,15 @@ pub fn group_versions_by_channel(version_infos: Vec<VersionInfo>) -> Vec<String>
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn latest_matches_newest_stable_installed_version() {
+        let installed = ["v1.21.6", "v1.22.3", "v1.23.0-rc.1"].map(String::from);
+        assert_eq!(match_installed_version(&installed, "latest").as_deref(), Some("v1.22.3"));
+        assert_eq!(match_installed_version(&[], "latest"), None);
+    }
+}
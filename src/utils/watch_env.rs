@@ -0,0 +1,226 @@
+use super::error::{Result, RvmError};
+use super::project::ProjectConfig;
+use super::version::get_runtime_home;
+use crate::runtime::{self, RUNTIMES};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+
+/// Environment file loaded from the project root
+const ENV_FILE: &str = ".env";
+
+/// Extra environment for the watched command: .env files, --env overrides and the bin
+/// directories of the project's pinned runtimes. Later sources win, --env overrides everything
+pub struct WatchEnv {
+    files: Vec<PathBuf>,
+    overrides: Vec<(String, String)>,
+    vars: BTreeMap<String, String>,
+    path_dirs: Vec<PathBuf>,
+}
+
+impl WatchEnv {
+    /// Read `.env` and, with a profile, `.env.<profile>` from `root`, and parse the KEY=VAL
+    /// overrides
+    pub fn new(root: &Path, profile: Option<&str>, overrides: &[String]) -> Result<Self> {
+        let overrides = overrides
+            .iter()
+            .map(|pair| {
+                parse_assignment(pair).ok_or_else(|| {
+                    RvmError::InvalidArguments(format!("--env expects KEY=VALUE, got {}", pair))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut files = vec![root.join(ENV_FILE)];
+        if let Some(profile) = profile {
+            let file = root.join(format!("{}.{}", ENV_FILE, profile));
+            // Asking for a profile without its file is most likely a typo
+            if !file.is_file() {
+                return Err(RvmError::FileNotFound(file.display().to_string()));
+            }
+            files.push(file);
+        }
+
+        let mut env = WatchEnv {
+            files,
+            overrides,
+            vars: BTreeMap::new(),
+            path_dirs: Vec::new(),
+        };
+        env.reload();
+        Ok(env)
+    }
+
+    /// Env files that exist, to be watched even when they are ignored or outside --watch paths
+    pub fn files(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().filter(|file| file.is_file())
+    }
+
+    pub fn is_env_file(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file == path)
+    }
+
+    /// Re-read the env files, lines with errors are reported and skipped
+    pub fn reload(&mut self) {
+        let mut vars = BTreeMap::new();
+        for file in &self.files {
+            let content = match std::fs::read_to_string(file) {
+                Ok(content) => content,
+                // .env is optional
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => {
+                    eprintln!("Warning: Failed to read {}: {}", file.display(), e);
+                    continue;
+                }
+            };
+
+            for (number, line) in content.lines().enumerate() {
+                match parse_line(line) {
+                    Ok(Some((key, value))) => {
+                        vars.insert(key, value);
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("Warning: {}:{}: {}", file.display(), number + 1, e);
+                    }
+                }
+            }
+        }
+        self.vars = vars;
+    }
+
+    /// Put directories first on the command's PATH
+    pub fn prepend_path(&mut self, dirs: Vec<PathBuf>) {
+        self.path_dirs = dirs;
+    }
+
+    /// Set the variables on a command, on top of the inherited environment
+    pub fn apply(&self, cmd: &mut Command) {
+        let mut vars = self.vars.clone();
+        vars.extend(self.overrides.iter().cloned());
+
+        if !self.path_dirs.is_empty() {
+            let path = vars
+                .remove("PATH")
+                .or_else(|| std::env::var("PATH").ok())
+                .unwrap_or_default();
+            let dirs = self
+                .path_dirs
+                .iter()
+                .map(|dir| dir.as_os_str().to_owned())
+                .chain(std::env::split_paths(&path).map(PathBuf::into_os_string));
+            if let Ok(path) = std::env::join_paths(dirs) {
+                cmd.env("PATH", path);
+            }
+        }
+
+        cmd.envs(vars);
+    }
+}
+
+/// Bin directories of the runtimes pinned for the project at `root` (rvm.toml, .tool-versions,
+/// .nvmrc, go.mod, ...), resolved the way `rvm use` would, with a "<runtime> <version>" label.
+/// Blocking, resolving a go.mod directive may fetch the release list
+pub fn pinned_runtime_dirs(root: &Path) -> Vec<(String, PathBuf)> {
+    let config = ProjectConfig::find_from(root).ok().flatten();
+    let mut dirs = Vec::new();
+
+    for name in RUNTIMES {
+        let Ok(rt) = runtime::get_runtime(name) else {
+            continue;
+        };
+        let hint = match config.as_ref().and_then(|config| config.pinned_version(name)) {
+            Some(hint) => hint,
+            None => match rt.project_version(root) {
+                Ok(Some(hint)) => hint,
+                Ok(None) => continue,
+                Err(e) => {
+                    eprintln!("Warning: Failed to read the project's {} version: {}", name, e);
+                    continue;
+                }
+            },
+        };
+
+        match rt.find_installed(&hint.version) {
+            Ok(Some(installed)) => {
+                if let Ok(home) = get_runtime_home(name) {
+                    let label = format!("{} {}", name, installed);
+                    dirs.push((label, home.join(&installed).join("bin")));
+                }
+            }
+            _ => eprintln!(
+                "Warning: {} {} from {} is not installed, run: rvm add {} {}",
+                name,
+                hint.version,
+                hint.source.display(),
+                name,
+                hint.version
+            ),
+        }
+    }
+    dirs
+}
+
+/// Split `KEY=VALUE`, None if there is no `=` or the key isn't a valid variable name
+fn parse_assignment(pair: &str) -> Option<(String, String)> {
+    let (key, value) = pair.split_once('=')?;
+    is_valid_key(key).then(|| (key.to_string(), value.to_string()))
+}
+
+fn is_valid_key(key: &str) -> bool {
+    !key.is_empty()
+        && !key.starts_with(|c: char| c.is_ascii_digit())
+        && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.')
+}
+
+/// Parse one line of an env file: `KEY=value`, optionally prefixed with `export`. Values may be
+/// single quoted (literal), double quoted (with \n, \t, \" and \\ escapes) or bare, where a
+/// ` #` starts a comment
+fn parse_line(line: &str) -> std::result::Result<Option<(String, String)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+
+    let line = line.strip_prefix("export ").map(str::trim_start).unwrap_or(line);
+    let Some((key, value)) = line.split_once('=') else {
+        return Err(format!("expected KEY=VALUE, got {}", line));
+    };
+    let key = key.trim();
+    if !is_valid_key(key) {
+        return Err(format!("invalid variable name {}", key));
+    }
+
+    let value = value.trim_start();
+    let value = if let Some(rest) = value.strip_prefix('\'') {
+        let (value, _) = rest
+            .split_once('\'')
+            .ok_or_else(|| format!("unterminated quote in {}", key))?;
+        value.to_string()
+    } else if let Some(rest) = value.strip_prefix('"') {
+        let mut unescaped = String::new();
+        let mut chars = rest.chars();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => match chars.next() {
+                    Some('n') => unescaped.push('\n'),
+                    Some('t') => unescaped.push('\t'),
+                    Some(c) => unescaped.push(c),
+                    None => return Err(format!("unterminated quote in {}", key)),
+                },
+                Some(c) => unescaped.push(c),
+                None => return Err(format!("unterminated quote in {}", key)),
+            }
+        }
+        unescaped
+    } else {
+        let value = match value.find(" #") {
+            Some(comment) => &value[..comment],
+            None => value,
+        };
+        value.trim_end().to_string()
+    };
+
+    Ok(Some((key.to_string(), value)))
+}
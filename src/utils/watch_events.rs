@@ -0,0 +1,168 @@
+use serde_json::{Value, json};
+use std::io;
+use std::os::unix::fs::FileTypeExt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::net::unix::pipe;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// How long closing the log waits for queued events, e.g. while a FIFO has no reader
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Format of the event stream written by --events
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum EventFormat {
+    /// One JSON object per line
+    Json,
+}
+
+/// Newline-delimited JSON log of what the watcher does, for editors and scripts to follow
+#[derive(Clone)]
+pub struct EventLog {
+    /// Shared by every clone, so closing the log ends the writer for all of them
+    lines: Arc<Mutex<Option<mpsc::UnboundedSender<String>>>>,
+    writer: Arc<Mutex<Option<JoinHandle<()>>>>,
+    /// Absolute path of the log with symlinks resolved, so watchers can leave it out
+    path: PathBuf,
+}
+
+impl EventLog {
+    /// Append events to a file or FIFO in the background. Events queue up until a FIFO has a
+    /// reader, without holding up the watcher
+    pub fn open(path: PathBuf) -> Self {
+        let canonical = canonical_path(&path);
+        let (lines, mut rx) = mpsc::unbounded_channel::<String>();
+
+        let writer = tokio::spawn(async move {
+            let mut sink = None;
+            while let Some(line) = rx.recv().await {
+                if sink.is_none() {
+                    match Sink::open(&path).await {
+                        Ok(opened) => sink = Some(opened),
+                        Err(e) => {
+                            eprintln!("Warning: Failed to open {}: {}", path.display(), e);
+                            continue;
+                        }
+                    }
+                }
+
+                // A FIFO whose reader went away is opened again for the next event
+                if let Some(opened) = sink.as_mut()
+                    && opened.write_all(line.as_bytes()).await.is_err()
+                {
+                    sink = None;
+                }
+            }
+        });
+
+        EventLog {
+            lines: Arc::new(Mutex::new(Some(lines))),
+            writer: Arc::new(Mutex::new(Some(writer))),
+            path: canonical,
+        }
+    }
+
+    /// Stop taking events and wait until the queued ones are written. Events emitted afterwards,
+    /// also through clones, are dropped
+    pub async fn close(&self) {
+        self.lines.lock().unwrap().take();
+        let writer = self.writer.lock().unwrap().take();
+        if let Some(mut writer) = writer
+            && tokio::time::timeout(CLOSE_TIMEOUT, &mut writer).await.is_err()
+        {
+            eprintln!("Warning: Gave up writing events to {}", self.path.display());
+            writer.abort();
+        }
+    }
+
+    /// The log file, as a watcher sees it when it lies inside the watched tree
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Write an event, e.g. `{"event":"exit","time":1700000000000,"task":"api","code":1}`.
+    /// `fields` must be a JSON object
+    pub fn emit(&self, task: Option<&str>, event: &str, fields: Value) {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_millis() as u64)
+            .unwrap_or_default();
+
+        let mut record = json!({ "event": event, "time": time });
+        if let Some(task) = task {
+            record["task"] = json!(task);
+        }
+        if let (Some(record), Value::Object(fields)) = (record.as_object_mut(), fields) {
+            record.extend(fields);
+        }
+
+        if let Some(lines) = self.lines.lock().unwrap().as_ref() {
+            let _ = lines.send(format!("{}\n", record));
+        }
+    }
+}
+
+/// Where events are written
+enum Sink {
+    File(File),
+    Fifo(pipe::Sender),
+}
+
+impl Sink {
+    /// How often to check for a reader while a FIFO has none
+    const READER_POLL: Duration = Duration::from_millis(250);
+
+    /// Open a FIFO without blocking, so waiting for its reader can be cancelled, or append to a
+    /// regular file
+    async fn open(path: &Path) -> io::Result<Self> {
+        let is_fifo = tokio::fs::metadata(path)
+            .await
+            .is_ok_and(|metadata| metadata.file_type().is_fifo());
+        if !is_fifo {
+            let file = OpenOptions::new().create(true).append(true).open(path).await?;
+            return Ok(Sink::File(file));
+        }
+
+        loop {
+            match pipe::OpenOptions::new().open_sender(path) {
+                Ok(sender) => return Ok(Sink::Fifo(sender)),
+                // No reader yet
+                Err(e) if e.raw_os_error() == Some(libc::ENXIO) => {
+                    tokio::time::sleep(Self::READER_POLL).await
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
+        match self {
+            // Flushed right away, so followers see each event and none is lost on exit
+            Sink::File(file) => {
+                file.write_all(bytes).await?;
+                file.flush().await
+            }
+            Sink::Fifo(sender) => sender.write_all(bytes).await,
+        }
+    }
+}
+
+/// Resolve a path that may not exist yet through its parent directory
+fn canonical_path(path: &Path) -> PathBuf {
+    if let Ok(canonical) = std::fs::canonicalize(path) {
+        return canonical;
+    }
+
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    match (std::fs::canonicalize(parent), path.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()),
+    }
+}
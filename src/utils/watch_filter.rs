@@ -0,0 +1,182 @@
+use super::error::{Result, RvmError};
+use super::watcher::WatchOptions;
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Ignore files read in every watched directory, later files take precedence
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".rvmignore"];
+
+/// Editor swap, backup and lock files that never warrant a restart (4913 is vim's write test)
+const BUILTIN_IGNORES: [&str; 9] = [
+    "*.swp",
+    "*.swo",
+    "*.swx",
+    "*~",
+    "4913",
+    ".#*",
+    "#*#",
+    "*.tmp",
+    ".DS_Store",
+];
+
+/// Decides which paths the watcher ignores. Precedence follows git: --ignore globs first, then
+/// the nearest .rvmignore/.gitignore, then .git/info/exclude, then the global gitignore
+pub struct WatchFilter {
+    overrides: Gitignore,
+    includes: Gitignore,
+    extensions: Vec<String>,
+    dirs: BTreeMap<PathBuf, Gitignore>,
+    exclude: Gitignore,
+    global: Gitignore,
+}
+
+impl WatchFilter {
+    pub fn new(root: &Path, options: &WatchOptions) -> Result<Self> {
+        let ignore_globs = BUILTIN_IGNORES
+            .iter()
+            .copied()
+            .chain(options.ignore.iter().map(String::as_str));
+        let overrides = Self::build_globs(root, ignore_globs)?;
+        let includes = Self::build_globs(root, options.include.iter().map(String::as_str))?;
+
+        // Accept "go", ".go" and "go,mod" style extensions
+        let extensions = options
+            .extensions
+            .iter()
+            .map(|ext| ext.trim().trim_start_matches('.').to_string())
+            .filter(|ext| !ext.is_empty())
+            .collect();
+
+        // .git/info/exclude belongs to the repository, which may start above the watched root
+        let git_root = root.ancestors().find(|dir| dir.join(".git").exists());
+        let exclude = match git_root {
+            Some(git_root) => {
+                let mut builder = GitignoreBuilder::new(git_root);
+                // A missing exclude file just means there is nothing to exclude
+                let _ = builder.add(git_root.join(".git").join("info").join("exclude"));
+                builder.build().unwrap_or_else(|_| Gitignore::empty())
+            }
+            None => Gitignore::empty(),
+        };
+
+        let (global, _) = Gitignore::global();
+
+        let mut filter = WatchFilter {
+            overrides,
+            includes,
+            extensions,
+            dirs: BTreeMap::new(),
+            exclude,
+            global,
+        };
+
+        // .gitignore files between the repository root and the watched root apply too
+        let top = git_root.unwrap_or(root);
+        for dir in root.ancestors().take_while(|dir| dir.starts_with(top)) {
+            filter.load_dir(dir);
+        }
+
+        Ok(filter)
+    }
+
+    /// Matcher for gitignore-style globs given on the command line, relative to `root`
+    fn build_globs<'a>(root: &Path, globs: impl Iterator<Item = &'a str>) -> Result<Gitignore> {
+        let mut builder = GitignoreBuilder::new(root);
+        for glob in globs {
+            builder.add_line(None, glob).map_err(|e| {
+                RvmError::InvalidArguments(format!("invalid glob {}: {}", glob, e))
+            })?;
+        }
+        builder
+            .build()
+            .map_err(|e| RvmError::InvalidArguments(e.to_string()))
+    }
+
+    /// (Re)read the ignore files of a directory
+    pub fn load_dir(&mut self, dir: &Path) {
+        let mut builder = GitignoreBuilder::new(dir);
+        for file_name in IGNORE_FILES {
+            let path = dir.join(file_name);
+            if path.is_file()
+                && let Some(e) = builder.add(&path)
+            {
+                eprintln!("Warning: Failed to read {}: {}", path.display(), e);
+            }
+        }
+
+        match builder.build() {
+            Ok(matcher) if !matcher.is_empty() => {
+                self.dirs.insert(dir.to_path_buf(), matcher);
+            }
+            _ => {
+                self.dirs.remove(dir);
+            }
+        }
+    }
+
+    /// Whether a path is an ignore file whose directory needs reloading after it changes
+    pub fn is_ignore_file(path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| IGNORE_FILES.iter().any(|file_name| name == *file_name))
+    }
+
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        // Git internals change on every commit or checkout
+        if path
+            .components()
+            .any(|component| component.as_os_str() == ".git")
+        {
+            return true;
+        }
+
+        let dir_matches = path
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| self.dirs.get(dir));
+
+        for matcher in std::iter::once(&self.overrides)
+            .chain(dir_matches)
+            .chain([&self.exclude, &self.global])
+        {
+            match Self::matched(matcher, path, is_dir) {
+                Match::Ignore(_) => return true,
+                Match::Whitelist(_) => return false,
+                Match::None => {}
+            }
+        }
+
+        false
+    }
+
+    /// Whether a change to the path should restart the command. With no --ext or --include
+    /// every path is selected, otherwise only files matching one of them
+    pub fn is_selected(&self, path: &Path, is_dir: bool) -> bool {
+        if self.extensions.is_empty() && self.includes.is_empty() {
+            return true;
+        }
+        if is_dir {
+            return false;
+        }
+
+        let extension_matches = path
+            .extension()
+            .is_some_and(|ext| self.extensions.iter().any(|wanted| ext == wanted.as_str()));
+
+        extension_matches || Self::matched(&self.includes, path, false).is_ignore()
+    }
+
+    fn matched<'a>(
+        matcher: &'a Gitignore,
+        path: &Path,
+        is_dir: bool,
+    ) -> Match<&'a ignore::gitignore::Glob> {
+        // Parent directories only need checking when the matcher's root is above the path
+        if path.starts_with(matcher.path()) {
+            matcher.matched_path_or_any_parents(path, is_dir)
+        } else {
+            matcher.matched(path, is_dir)
+        }
+    }
+}
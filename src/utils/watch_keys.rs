@@ -0,0 +1,86 @@
+use std::io::Write;
+use termion::event::Key;
+use termion::input::TermRead;
+use tokio::sync::broadcast;
+
+/// Shown instead of the plain Ctrl+C hint when keyboard controls are on
+pub const KEY_HELP: &str =
+    "Press r to restart, p to pause, l to list the last changes, c to clear, q or Ctrl+C to quit";
+
+/// Keys handled by every watcher, clearing the screen is done once by the reader itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKey {
+    Restart,
+    Pause,
+    Quit,
+    List,
+}
+
+/// Keyboard controls for the terminal rvm watch runs in. Keys are read without waiting for
+/// Enter until this is dropped, which restores the terminal
+pub struct KeyControls {
+    keys: broadcast::Sender<WatchKey>,
+    original: libc::termios,
+}
+
+impl KeyControls {
+    /// Start reading keys, None if stdin isn't a terminal (e.g. piped input or CI)
+    pub fn enable() -> Option<Self> {
+        if !termion::is_tty(&std::io::stdin()) {
+            return None;
+        }
+
+        // SAFETY: termios is plain data, tcgetattr fills it in and fails on a non-terminal
+        let mut original: libc::termios = unsafe { std::mem::zeroed() };
+        if unsafe { libc::tcgetattr(libc::STDIN_FILENO, &mut original) } != 0 {
+            return None;
+        }
+
+        // No line buffering or echo, but keep signals (Ctrl+C) and output processing, unlike
+        // termion's raw mode which would break the command's line endings
+        let mut cbreak = original;
+        cbreak.c_lflag &= !(libc::ICANON | libc::ECHO);
+        cbreak.c_cc[libc::VMIN] = 1;
+        cbreak.c_cc[libc::VTIME] = 0;
+        // SAFETY: `cbreak` is a valid termios copied from the terminal
+        if unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &cbreak) } != 0 {
+            return None;
+        }
+
+        let (keys, _) = broadcast::channel(16);
+        let sender = keys.clone();
+        // Blocking reads on stdin, the thread simply ends with the process
+        std::thread::spawn(move || {
+            for key in std::io::stdin().keys().flatten() {
+                let key = match key {
+                    Key::Char('r') | Key::Char('R') => WatchKey::Restart,
+                    Key::Char('p') | Key::Char('P') => WatchKey::Pause,
+                    Key::Char('q') | Key::Char('Q') => WatchKey::Quit,
+                    Key::Char('l') | Key::Char('L') => WatchKey::List,
+                    Key::Char('c') | Key::Char('C') => {
+                        print!("{}{}", termion::clear::All, termion::cursor::Goto(1, 1));
+                        let _ = std::io::stdout().flush();
+                        continue;
+                    }
+                    _ => continue,
+                };
+                // Nobody listening just means the watchers are shutting down
+                let _ = sender.send(key);
+            }
+        });
+
+        Some(KeyControls { keys, original })
+    }
+
+    /// Receiver for the keys pressed from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<WatchKey> {
+        self.keys.subscribe()
+    }
+}
+
+impl Drop for KeyControls {
+    fn drop(&mut self) {
+        // SAFETY: `original` was read from the same terminal in enable()
+        unsafe { libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.original) };
+    }
+}
@@ -0,0 +1,108 @@
+use super::error::{Result, RvmError};
+use regex_automata::meta::Regex;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::Notify;
+use tokio::time::sleep;
+
+/// Time between two port or URL probes
+const PROBE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How to tell that a restarted service is up
+#[derive(Clone)]
+pub enum ReadyCheck {
+    /// A TCP port on localhost accepts connections
+    Port(u16),
+    /// A line of output matches the pattern
+    Log(Regex, String),
+    /// A GET request answers with a 2xx status
+    Url(String),
+}
+
+impl ReadyCheck {
+    /// The check given by at most one of the --ready-* options
+    pub fn from_options(
+        port: Option<u16>,
+        log: Option<&str>,
+        url: Option<&str>,
+    ) -> Result<Option<Self>> {
+        let check = match (port, log, url) {
+            (None, None, None) => return Ok(None),
+            (Some(port), None, None) => ReadyCheck::Port(port),
+            (None, Some(pattern), None) => {
+                let regex = Regex::new(pattern).map_err(|e| {
+                    RvmError::InvalidArguments(format!("invalid ready pattern {}: {}", pattern, e))
+                })?;
+                ReadyCheck::Log(regex, pattern.to_string())
+            }
+            (None, None, Some(url)) => ReadyCheck::Url(url.to_string()),
+            _ => {
+                return Err(RvmError::InvalidArguments(
+                    "only one of ready port, log and url can be used".to_string(),
+                ));
+            }
+        };
+        Ok(Some(check))
+    }
+
+    /// Short description for messages, e.g. "port 3000"
+    pub fn describe(&self) -> String {
+        match self {
+            ReadyCheck::Port(port) => format!("port {}", port),
+            ReadyCheck::Log(_, pattern) => format!("output /{}/", pattern),
+            ReadyCheck::Url(url) => url.clone(),
+        }
+    }
+
+    /// Matcher to feed the command's output to, for log checks
+    pub fn log_match(&self) -> Option<LogMatch> {
+        match self {
+            ReadyCheck::Log(regex, _) => Some(LogMatch {
+                regex: regex.clone(),
+                matched: Arc::new(Notify::new()),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Wait until the service is ready. `log` is the matcher from log_match that sees the output
+    pub async fn wait(&self, log: Option<LogMatch>) {
+        match self {
+            ReadyCheck::Port(port) => {
+                while TcpStream::connect(("localhost", *port)).await.is_err() {
+                    sleep(PROBE_INTERVAL).await;
+                }
+            }
+            ReadyCheck::Log(..) => match log {
+                Some(log) => log.matched.notified().await,
+                None => std::future::pending().await,
+            },
+            ReadyCheck::Url(url) => {
+                let client = reqwest::Client::new();
+                loop {
+                    let response = client.get(url).timeout(Duration::from_secs(2)).send().await;
+                    if response.is_ok_and(|response| response.status().is_success()) {
+                        return;
+                    }
+                    sleep(PROBE_INTERVAL).await;
+                }
+            }
+        }
+    }
+}
+
+/// Signals a log readiness check once a line of output matches
+#[derive(Clone)]
+pub struct LogMatch {
+    regex: Regex,
+    matched: Arc<Notify>,
+}
+
+impl LogMatch {
+    pub fn check(&self, line: &str) {
+        if self.regex.is_match(line) {
+            self.matched.notify_one();
+        }
+    }
+}
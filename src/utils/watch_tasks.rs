@@ -0,0 +1,109 @@
+use super::error::{Result, RvmError};
+use super::livereload::LiveReload;
+use super::watch_events::EventLog;
+use super::project::ProjectConfig;
+use super::ui::display_error;
+use super::watch_keys::{KEY_HELP, KeyControls};
+use super::watcher::{FileWatcher, WatchOptions};
+use termion::color;
+
+/// 256-color palette indexes cycled through for task labels (cyan, magenta, yellow, green, blue)
+const TASK_COLORS: [u8; 5] = [6, 5, 3, 2, 4];
+
+/// Run every `[watch.<name>]` task from the project's rvm.toml side by side, each with its own
+/// watcher and a colored prefix on its output
+pub async fn run_project_tasks(
+    options: WatchOptions,
+    controls: Option<&KeyControls>,
+    livereload: Option<LiveReload>,
+    event_log: Option<EventLog>,
+) -> Result<()> {
+    let config = ProjectConfig::find()?
+        .filter(|config| !config.watch.is_empty())
+        .ok_or_else(|| {
+            RvmError::MissingArgument(
+                "command (or [watch.<name>] tasks in rvm.toml)".to_string(),
+            )
+        })?;
+
+    // These describe a single command, each task sets its own in rvm.toml
+    let per_task = [
+        ("--watch", !options.paths.is_empty()),
+        ("--build", options.build.is_some()),
+        ("--ready-port/--ready-log/--ready-url", options.ready.is_some()),
+    ];
+    let given: Vec<&str> = per_task
+        .iter()
+        .filter(|(_, set)| *set)
+        .map(|(flag, _)| *flag)
+        .collect();
+    if !given.is_empty() {
+        return Err(RvmError::InvalidArguments(format!(
+            "{} can't be combined with [watch.<name>] tasks, set paths, build or ready_* on the \
+             tasks in {}",
+            given.join(", "),
+            config.file.display()
+        )));
+    }
+
+    let root = config.root().to_path_buf();
+    let width = config.watch.keys().map(|name| name.len()).max().unwrap_or(0);
+
+    // Create every watcher first so a bad task fails before anything is started
+    let mut watchers = Vec::new();
+    for (index, (name, task)) in config.watch.iter().enumerate() {
+        let label = format!(
+            "{}{:width$} |{}",
+            color::Fg(color::AnsiValue(TASK_COLORS[index % TASK_COLORS.len()])),
+            name,
+            color::Fg(color::Reset),
+            width = width
+        );
+        let mut watcher = FileWatcher::for_task(name, task, options.clone(), &root, label)?;
+        if let Some(controls) = controls {
+            watcher.set_keys(controls.subscribe());
+        }
+        if let Some(server) = &livereload {
+            watcher.set_livereload(server.clone());
+        }
+        if let Some(event_log) = &event_log {
+            watcher.set_event_log(event_log.clone());
+        }
+        watchers.push((name.clone(), watcher));
+    }
+
+    println!(
+        "Running {} watch tasks from {}",
+        watchers.len(),
+        config.file.display()
+    );
+    if let Some(server) = &livereload {
+        println!("Live reload: add {} to your page", server.snippet_tag());
+    }
+    println!("{}", if controls.is_some() { KEY_HELP } else { "Press Ctrl+C to stop" });
+
+    let handles: Vec<_> = watchers
+        .into_iter()
+        .map(|(name, mut watcher)| (name, tokio::spawn(async move { watcher.start().await })))
+        .collect();
+
+    let mut failed = Vec::new();
+    for (name, handle) in handles {
+        match handle.await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                display_error(&format!("Watch task {} failed: {}", name, e));
+                failed.push(name);
+            }
+            Err(e) => {
+                display_error(&format!("Watch task {} crashed: {}", name, e));
+                failed.push(name);
+            }
+        }
+    }
+
+    if !failed.is_empty() {
+        return Err(RvmError::WatchCommandFailed(failed.join(", ")));
+    }
+    Ok(())
+}
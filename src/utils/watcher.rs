@@ -1,224 +1,1384 @@
 use super::error::{Result, RvmError};
-use ignore::WalkBuilder;
-use notify::{Config, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
-use std::path::{PathBuf};
-use std::process::Stdio;
-use std::sync::mpsc;
+use super::livereload::{LiveReload, Reload};
+use super::project::WatchTask;
+use super::watch_env::{WatchEnv, pinned_runtime_dirs};
+use super::watch_events::EventLog;
+use super::watch_filter::WatchFilter;
+use super::watch_keys::{KEY_HELP, WatchKey};
+use super::watch_ready::{LogMatch, ReadyCheck};
+use notify::{Config, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher, Event, EventKind};
+use serde_json::json;
+use std::collections::BTreeSet;
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{ExitStatus, Stdio};
 use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
 use tokio::process::{Child, Command};
-use tokio::time::{sleep, Instant};
+use tokio::sync::{broadcast, mpsc};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, sleep_until, timeout, Instant};
+
+/// Watch settings given on the command line
+#[derive(Clone)]
+pub struct WatchOptions {
+    /// Quiet period after the last change before restarting
+    pub debounce: Duration,
+    /// Minimum seconds between two restarts, None allows the legacy trailing -<seconds>
+    pub delay: Option<u64>,
+    /// Whether the command followed a `--` separator, which turns off the legacy -<seconds>
+    pub separated: bool,
+    /// Extra gitignore-style globs to ignore
+    pub ignore: Vec<String>,
+    /// Directories or files to watch instead of the current directory
+    pub paths: Vec<PathBuf>,
+    /// Only restart for files with these extensions
+    pub extensions: Vec<String>,
+    /// Only restart for files matching these gitignore-style globs
+    pub include: Vec<String>,
+    /// Signal asking the command to stop
+    pub stop_signal: StopSignal,
+    /// Time the command gets to stop before it is killed
+    pub grace_period: Duration,
+    /// Shell command line that has to succeed before the command is (re)started
+    pub build: Option<String>,
+    /// Whether the command is restarted when it exits by itself
+    pub restart: RestartPolicy,
+    /// Restarts in a row before giving up and waiting for changes
+    pub max_retries: u32,
+    /// Stop watching once the command exits successfully
+    pub exit_on_success: bool,
+    /// Poll for changes at this interval instead of using inotify
+    pub poll: Option<Duration>,
+    /// Also load .env.<profile> after .env
+    pub profile: Option<String>,
+    /// KEY=VALUE variables set on the command, over the env files
+    pub env: Vec<String>,
+    /// How to tell that the command is up after a (re)start
+    pub ready: Option<ReadyCheck>,
+    /// Time the command gets to become ready
+    pub ready_timeout: Duration,
+    /// Shell command line run after each batch of changes
+    pub on_change: Option<String>,
+    /// Shell command line run each time the command exits
+    pub on_exit: Option<String>,
+}
+
+/// Signal sent to the command's process group when it is restarted or rvm exits
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum StopSignal {
+    #[value(name = "SIGTERM", alias = "TERM")]
+    Term,
+    #[value(name = "SIGINT", alias = "INT")]
+    Int,
+}
+
+impl StopSignal {
+    fn as_raw(self) -> libc::c_int {
+        match self {
+            StopSignal::Term => libc::SIGTERM,
+            StopSignal::Int => libc::SIGINT,
+        }
+    }
+}
+
+/// When the command is restarted after it exits by itself
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RestartPolicy {
+    /// Only after a non-zero exit or a signal
+    OnFailure,
+    Always,
+    /// Wait for the next change
+    Never,
+}
+
+/// Delay before the first restart after an exit, doubled for every restart in a row
+const BACKOFF_START: Duration = Duration::from_secs(1);
+const BACKOFF_MAX: Duration = Duration::from_secs(30);
+/// A run lasting this long counts as healthy and resets the backoff
+const HEALTHY_RUN: Duration = Duration::from_secs(10);
+
+/// Poll interval used when inotify can't be used
+const FALLBACK_POLL_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Messages handled by the watch loop
+enum WatchMessage {
+    Change(Event),
+    /// The command exited by itself, None if its status could not be read
+    Exited(Option<ExitStatus>),
+    /// The backoff after an exit is over and the command is due to restart
+    Retry,
+    /// The readiness check of a run finished, `ready` is false if it timed out
+    Ready { run: u64, ready: bool },
+    Key(WatchKey),
+    Shutdown,
+}
+
+/// What the watch loop does after a key press
+#[derive(PartialEq, Eq)]
+enum KeyAction {
+    Quit,
+    Restart,
+    Continue,
+}
+
+/// A started command and its process group
+struct RunningProcess {
+    child: Child,
+    pgid: u32,
+    started: Instant,
+    exited: bool,
+    /// Number of the run, to match readiness results
+    run: u64,
+    /// Readiness check in progress
+    ready_task: Option<JoinHandle<()>>,
+}
+
+impl Drop for RunningProcess {
+    fn drop(&mut self) {
+        if let Some(task) = &self.ready_task {
+            task.abort();
+        }
+    }
+}
 
 pub struct FileWatcher {
+    /// Shell command line to run
     command: String,
-    args: Vec<String>,
+    /// Minimum time between two restarts
     interval: u64,
-    current_process: Option<Child>,
-    watch_paths: Vec<PathBuf>,
+    options: WatchOptions,
+    /// Directory the command runs in
+    root: PathBuf,
+    /// Prefix for output when several tasks run side by side
+    label: Option<String>,
+    /// Name of the rvm.toml task, for events and hooks
+    task: Option<String>,
+    /// Whether the command is a build step expected to exit, rather than a service
+    oneshot: bool,
+    current_process: Option<RunningProcess>,
+    /// Runs started so far
+    runs: u64,
+    /// Sender of the watch loop's channel, for readiness results
+    events: Option<mpsc::UnboundedSender<WatchMessage>>,
+    /// Server telling browsers to reload, with --livereload
+    livereload: Option<LiveReload>,
+    /// Where events are written, with --events
+    event_log: Option<EventLog>,
+    /// Browser reload waiting for the current run to finish or become ready
+    pending_reload: Option<Reload>,
+    /// Restarts in a row after the command exited by itself
+    retries: u32,
+    /// When the command is due to restart after it exited by itself
+    restart_at: Option<Instant>,
+    /// Keys pressed in the terminal, when keyboard controls are on
+    keys: Option<broadcast::Receiver<WatchKey>>,
+    /// Whether changes are held back until watching resumes
+    paused: bool,
+    paused_changes: BTreeSet<PathBuf>,
+    /// Files that triggered the last restart
+    last_changes: BTreeSet<PathBuf>,
+    watch_paths: BTreeSet<PathBuf>,
+    filter: WatchFilter,
+    env: WatchEnv,
+    /// Whether an env file changed since it was last read
+    env_changed: bool,
 }
 
 impl FileWatcher {
-    pub fn new(args: Vec<String>) -> Result<Self> {
-        let (command, args, interval) = Self::parse_args(args)?;
-        
-        // Build list of directories to watch, respecting .gitignore
-        let watch_paths = Self::build_watch_paths()?;
+    pub fn new(command: Vec<String>, options: WatchOptions) -> Result<Self> {
+        let (command, interval) = Self::parse_command(command, options.delay, options.separated)?;
+
+        // Absolute paths so event paths can be compared against the watched directories
+        let root = std::env::current_dir()?;
+        Self::build(command, interval, options, root)
+    }
+
+    /// Watcher for a named task from rvm.toml. Its filters are added to the command line ones,
+    /// its paths, build step and readiness check are its own
+    pub fn for_task(
+        name: &str,
+        task: &WatchTask,
+        mut options: WatchOptions,
+        root: &Path,
+        label: String,
+    ) -> Result<Self> {
+        options.paths = task.paths.clone();
+        options.extensions.extend(task.ext.iter().cloned());
+        options.include.extend(task.include.iter().cloned());
+        options.ignore.extend(task.ignore.iter().cloned());
+        options.build = task.build.clone();
+        options.ready = ReadyCheck::from_options(
+            task.ready_port,
+            task.ready_log.as_deref(),
+            task.ready_url.as_deref(),
+        )?;
+
+        let interval = options.delay.unwrap_or(0);
+        let mut watcher = Self::build(task.command.clone(), interval, options, root.to_path_buf())?;
+        watcher.label = Some(label);
+        watcher.task = Some(name.to_string());
+        watcher.oneshot = task.oneshot;
+        Ok(watcher)
+    }
+
+    fn build(command: String, interval: u64, options: WatchOptions, root: PathBuf) -> Result<Self> {
+        let mut filter = WatchFilter::new(&root, &options)?;
+        let env = WatchEnv::new(&root, options.profile.as_deref(), &options.env)?;
+
+        let roots = if options.paths.is_empty() {
+            vec![root.clone()]
+        } else {
+            options
+                .paths
+                .iter()
+                .map(|path| {
+                    std::fs::canonicalize(root.join(path)).map_err(|_| {
+                        RvmError::FileNotFound(format!("watch path {}", path.display()))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        // Build list of directories to watch, respecting the ignore files
+        let mut watch_paths = BTreeSet::new();
+        for path in &roots {
+            watch_paths.extend(Self::collect_dirs(&mut filter, path));
+        }
         
         Ok(FileWatcher {
             command,
-            args,
             interval,
+            options,
+            root,
+            label: None,
+            task: None,
+            oneshot: false,
             current_process: None,
+            runs: 0,
+            events: None,
+            livereload: None,
+            event_log: None,
+            pending_reload: None,
+            retries: 0,
+            restart_at: None,
+            keys: None,
+            paused: false,
+            paused_changes: BTreeSet::new(),
+            last_changes: BTreeSet::new(),
             watch_paths,
+            filter,
+            env,
+            env_changed: false,
         })
     }
-    
-    fn build_watch_paths() -> Result<Vec<PathBuf>> {
-        let mut paths = Vec::new();
-        
-        let walker = WalkBuilder::new(".")
-            .git_ignore(true)
-            .git_global(false)
-            .git_exclude(false)
-            .hidden(false)
-            .build();
-            
-        for entry in walker {
-            match entry {
-                Ok(entry) => {
-                    if entry.path().is_dir() {
-                        paths.push(entry.path().to_path_buf());
+
+    /// Handle keys from the terminal, the command's stdin is closed so it doesn't compete for them
+    pub fn set_keys(&mut self, keys: broadcast::Receiver<WatchKey>) {
+        self.keys = Some(keys);
+    }
+
+    /// Reload connected browsers after each restart or build
+    pub fn set_livereload(&mut self, server: LiveReload) {
+        self.livereload = Some(server);
+    }
+
+    /// Write change, restart, exit and ready events to a log
+    pub fn set_event_log(&mut self, event_log: EventLog) {
+        self.event_log = Some(event_log);
+    }
+
+    /// Message with the task prefix, if any
+    fn prefixed(&self, message: &str) -> String {
+        match &self.label {
+            Some(label) => format!("{} {}", label, message),
+            None => message.to_string(),
+        }
+    }
+
+    /// Directories at or below `root` that are not ignored, loading their ignore files on the way
+    fn collect_dirs(filter: &mut WatchFilter, root: &Path) -> BTreeSet<PathBuf> {
+        let mut dirs = BTreeSet::new();
+        let mut pending = vec![root.to_path_buf()];
+
+        while let Some(dir) = pending.pop() {
+            filter.load_dir(&dir);
+
+            if let Ok(entries) = std::fs::read_dir(&dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    // Symlinked directories are not followed
+                    let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                    if is_dir && !filter.is_ignored(&path, true) {
+                        pending.push(path);
                     }
                 }
-                Err(_) => continue,
             }
+
+            dirs.insert(dir);
         }
-        
-        // Always include current directory
-        if !paths.contains(&PathBuf::from(".")) {
-            paths.insert(0, PathBuf::from("."));
+
+        dirs
+    }
+
+    /// Start watching directories that appeared and drop the ones that were deleted or renamed
+    /// away, then drop ignored or unselected paths from the event
+    fn track_directories(&mut self, backend: &mut WatchBackend, event: &mut Event) {
+        for path in &event.paths {
+            if path.is_dir() && !self.watch_paths.contains(path) {
+                if self.filter.is_ignored(path, true) {
+                    continue;
+                }
+
+                // New directory (created or renamed into the tree), watch it and its subdirectories
+                let dirs = Self::collect_dirs(&mut self.filter, path);
+                self.watch_dirs(backend, dirs);
+            } else if !path.exists() && self.watch_paths.contains(path) {
+                // Deleted or renamed away, forget it and everything below it
+                let removed: Vec<PathBuf> = self
+                    .watch_paths
+                    .iter()
+                    .filter(|dir| dir.starts_with(path))
+                    .cloned()
+                    .collect();
+                for dir in removed {
+                    // The kernel usually drops the watch itself, so errors are expected here
+                    let _ = backend.unwatch(&dir);
+                    self.watch_paths.remove(&dir);
+                }
+                continue;
+            }
+
+            if self.env.is_env_file(path) {
+                self.env_changed = true;
+            }
+
+            // Edited ignore files take effect for the following events
+            if WatchFilter::is_ignore_file(path)
+                && let Some(dir) = path.parent()
+            {
+                self.filter.load_dir(dir);
+            }
         }
-        
-        Ok(paths)
+
+        let watch_paths = &self.watch_paths;
+        let filter = &self.filter;
+        let env = &self.env;
+        event.paths.retain(|path| {
+            // A deleted path can only be told apart from a file if it was a watched directory
+            let is_dir = path.is_dir() || (!path.exists() && watch_paths.contains(path));
+            // .env is usually gitignored, but still changes what the command sees
+            env.is_env_file(path)
+                || (!filter.is_ignored(path, is_dir) && filter.is_selected(path, is_dir))
+        });
     }
 
-    fn parse_args(args: Vec<String>) -> Result<(String, Vec<String>, u64)> {
+    /// Watch directories (non-recursively, to leave ignored subdirectories out). When inotify runs
+    /// out of watches, explain the limit and switch to polling instead of watching half blind
+    fn watch_dirs(&mut self, backend: &mut WatchBackend, dirs: BTreeSet<PathBuf>) {
+        let mut dirs = dirs.into_iter();
+        while let Some(dir) = dirs.next() {
+            match backend.watch(&dir) {
+                Ok(()) => {
+                    self.watch_paths.insert(dir);
+                }
+                Err(e) if !backend.is_polling() && is_watch_limit(&e) => {
+                    self.watch_paths.insert(dir);
+                    self.watch_paths.extend(dirs.by_ref());
+                    eprintln!("{}", self.prefixed(&watch_limit_report(self.watch_paths.len())));
+
+                    let failed = backend.switch_to_polling(&self.watch_paths);
+                    for (dir, e) in failed {
+                        let warning = format!("Warning: Failed to watch {}: {}", dir.display(), e);
+                        eprintln!("{}", self.prefixed(&warning));
+                        self.watch_paths.remove(&dir);
+                    }
+                }
+                Err(e) => {
+                    let warning = format!("Warning: Failed to watch {}: {}", dir.display(), e);
+                    eprintln!("{}", self.prefixed(&warning));
+                }
+            }
+        }
+    }
+
+    /// Turn the command arguments into a shell command line and the minimum time between
+    /// restarts. A single argument is used as a shell command line as-is (e.g. "cargo build &&
+    /// ./app"), several arguments are quoted so each reaches the program unchanged
+    fn parse_command(
+        mut args: Vec<String>,
+        delay: Option<u64>,
+        separated: bool,
+    ) -> Result<(String, u64)> {
         if args.is_empty() {
             return Err(RvmError::MissingArgument("command".to_string()));
         }
 
-        // Check if first arg is a quoted command (contains spaces)
-        if args[0].contains(' ') {
-            // First arg is quoted - split it and look for interval in remaining args
-            let parts: Vec<&str> = args[0].split_whitespace().collect();
-            let command = parts[0].to_string();
-            let mut filtered_args: Vec<String> = parts[1..].iter().map(|s| s.to_string()).collect();
-            
-            let mut interval = None;
-            let mut interval_index = None;
-
-            // Look for interval in remaining args (args[1..])
-            for (i, arg) in args[1..].iter().enumerate() {
-                if arg.starts_with('-') && arg.len() > 1 && arg[1..].chars().all(|c| c.is_ascii_digit()) {
-                    if let Ok(seconds) = arg[1..].parse::<u64>() {
-                        interval = Some(seconds);
-                        interval_index = Some(i);
+        // Legacy syntax: a trailing -<seconds> argument, only when neither --delay nor -- is given
+        let mut delay_seconds = delay.unwrap_or(0);
+        if delay.is_none()
+            && !separated
+            && args.len() > 1
+            && let Some(seconds) = args
+                .last()
+                .and_then(|arg| arg.strip_prefix('-'))
+                .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()))
+                .and_then(|digits| digits.parse::<u64>().ok())
+        {
+            println!(
+                "Warning: the trailing -{0} interval is deprecated, use --delay {0} instead",
+                seconds
+            );
+            args.pop();
+            delay_seconds = seconds;
+        }
+
+        let command = if args.len() == 1 {
+            args.remove(0)
+        } else {
+            args.iter()
+                .map(|arg| shell_quote(arg))
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        Ok((command, delay_seconds))
+    }
+
+    pub async fn start(&mut self) -> Result<()> {
+        println!(
+            "{}",
+            self.prefixed(&format!(
+                "Watching for changes... (debounce: {}ms, minimum between restarts: {}s)",
+                self.options.debounce.as_millis(),
+                self.interval
+            ))
+        );
+        if let Some(interval) = self.options.poll {
+            let polling = format!("Polling for changes every {}ms", interval.as_millis());
+            println!("{}", self.prefixed(&polling));
+        }
+        if !self.options.extensions.is_empty() || !self.options.include.is_empty() {
+            let mut selection: Vec<String> =
+                self.options.extensions.iter().map(|ext| format!("*.{}", ext)).collect();
+            selection.extend(self.options.include.iter().cloned());
+            let selection = format!("Only restarting for: {}", selection.join(", "));
+            println!("{}", self.prefixed(&selection));
+        }
+        if let Some(server) = self.livereload.as_ref().filter(|_| self.label.is_none()) {
+            println!("Live reload: add {} to your page", server.snippet_tag());
+        }
+        if self.label.is_none() {
+            let help = if self.keys.is_some() { KEY_HELP } else { "Press Ctrl+C to stop" };
+            println!("{}", help);
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        self.events = Some(tx.clone());
+
+        if let Some(keys) = &self.keys {
+            let mut keys = keys.resubscribe();
+            let key_tx = tx.clone();
+            tokio::spawn(async move {
+                loop {
+                    match keys.recv().await {
+                        Ok(key) => {
+                            if key_tx.send(WatchMessage::Key(key)).is_err() {
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(broadcast::error::RecvError::Closed) => break,
                     }
                 }
+            });
+        }
+
+        // The command runs in its own process group, so Ctrl+C reaches rvm only and is forwarded
+        let shutdown_tx = tx.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                let _ = shutdown_tx.send(WatchMessage::Shutdown);
             }
+        });
+        
+        let mut backend = match self.options.poll {
+            Some(interval) => WatchBackend::poll(tx, interval)?,
+            None => WatchBackend::inotify(tx).or_else(|(tx, e)| {
+                let warning = format!(
+                    "Warning: inotify is unavailable ({}), polling every {}ms instead",
+                    e,
+                    FALLBACK_POLL_INTERVAL.as_millis()
+                );
+                eprintln!("{}", self.prefixed(&warning));
+                WatchBackend::poll(tx, FALLBACK_POLL_INTERVAL)
+            })?,
+        };
+
+        let dirs = std::mem::take(&mut self.watch_paths);
+        self.watch_dirs(&mut backend, dirs);
 
-            // Add remaining args except the interval
-            for (i, arg) in args[1..].iter().enumerate() {
-                if Some(i) != interval_index {
-                    filtered_args.push(arg.clone());
+        // Env files outside the watched directories (e.g. with --watch src) are watched directly
+        let env_files: Vec<PathBuf> = self
+            .env
+            .files()
+            .filter(|file| file.parent().is_none_or(|dir| !self.watch_paths.contains(dir)))
+            .cloned()
+            .collect();
+        for file in env_files {
+            if let Err(e) = backend.watch(&file) {
+                let warning = format!("Warning: Failed to watch {}: {}", file.display(), e);
+                eprintln!("{}", self.prefixed(&warning));
+            }
+        }
+
+        // Same runtime versions as `rvm use` would pick for the project
+        let root = self.root.clone();
+        let runtimes = tokio::task::spawn_blocking(move || pinned_runtime_dirs(&root))
+            .await
+            .unwrap_or_default();
+        if !runtimes.is_empty() {
+            let labels: Vec<&str> = runtimes.iter().map(|(label, _)| label.as_str()).collect();
+            println!("{}", self.prefixed(&format!("Using {}", labels.join(", "))));
+        }
+        self.env
+            .prepend_path(runtimes.into_iter().map(|(_, dir)| dir).collect());
+        let env_files: Vec<String> = self
+            .env
+            .files()
+            .filter_map(|file| file.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect();
+        if !env_files.is_empty() {
+            let loaded = format!("Loaded environment from {}", env_files.join(", "));
+            println!("{}", self.prefixed(&loaded));
+        }
+
+        // Orphaned grandchildren are re-parented to rvm instead of init, so they can be reaped
+        // after a restart even where init doesn't reap (e.g. containers)
+        // SAFETY: PR_SET_CHILD_SUBREAPER only changes how orphans of this process are re-parented
+        unsafe { libc::prctl(libc::PR_SET_CHILD_SUBREAPER, 1, 0, 0, 0) };
+
+        // Start initial process, unless it doesn't build
+        if self.run_build().await {
+            self.restart_process().await?;
+        }
+
+        let mut last_restart = Instant::now();
+        let min_gap = Duration::from_secs(self.interval);
+
+        // Wait for the first change of a burst
+        'watch: while let Some(message) = self.next_message(&mut rx, true).await {
+            let event = match message {
+                WatchMessage::Change(event) => event,
+                WatchMessage::Exited(status) => {
+                    if self.handle_exit(status) {
+                        break;
+                    }
+                    continue;
+                }
+                WatchMessage::Retry => {
+                    self.restart_process().await?;
+                    self.schedule_reload(Reload::Page);
+                    continue;
+                }
+                WatchMessage::Ready { run, ready } => {
+                    self.handle_ready(run, ready);
+                    continue;
+                }
+                WatchMessage::Key(key) => {
+                    let mut resumed = BTreeSet::new();
+                    match self.handle_key(key, &mut resumed) {
+                        KeyAction::Quit => break,
+                        KeyAction::Restart => {
+                            self.reload("Restart requested", Reload::Page).await?;
+                            last_restart = Instant::now();
+                        }
+                        KeyAction::Continue if !resumed.is_empty() => {
+                            let reason = format!("{} file(s) changed while paused", resumed.len());
+                            self.report_changes(&resumed);
+                            let browsers = Reload::for_changes(&resumed);
+                            self.last_changes = resumed;
+                            self.reload(&reason, browsers).await?;
+                            last_restart = Instant::now();
+                        }
+                        KeyAction::Continue => {}
+                    }
+                    continue;
+                }
+                WatchMessage::Shutdown => break,
+            };
+            let mut changed = BTreeSet::new();
+            self.collect_change(&mut backend, event, &mut changed);
+
+            // Trailing-edge debounce: keep collecting until nothing changed for the quiet period
+            loop {
+                match timeout(self.options.debounce, self.next_message(&mut rx, false)).await {
+                    Ok(Some(WatchMessage::Change(event))) => {
+                        self.collect_change(&mut backend, event, &mut changed)
+                    }
+                    Ok(Some(WatchMessage::Exited(status))) => {
+                        if self.handle_exit(status) {
+                            break 'watch;
+                        }
+                    }
+                    // A restart follows the burst anyway
+                    Ok(Some(WatchMessage::Retry)) => {}
+                    Ok(Some(WatchMessage::Ready { run, ready })) => self.handle_ready(run, ready),
+                    Ok(Some(WatchMessage::Key(key))) => {
+                        if self.handle_key(key, &mut changed) == KeyAction::Quit {
+                            break 'watch;
+                        }
+                    }
+                    Ok(Some(WatchMessage::Shutdown)) | Ok(None) => break 'watch,
+                    Err(_) => break,
                 }
             }
 
-            let final_interval = interval.unwrap_or_else(|| {
-                println!("Warning: No interval specified. Default is 5 seconds.");
-                println!("Press Enter to continue or Ctrl+C to cancel...");
-                std::io::stdin().read_line(&mut String::new()).unwrap();
-                5
-            });
-            
-            return Ok((command, filtered_args, final_interval));
-        }
-
-        // No quoted command - find last -<number> as interval
-        let command = args[0].clone();
-        let mut filtered_args = Vec::new();
-        let mut interval = None;
-        let mut interval_index = None;
-
-        // Find the last -<digits> argument
-        for (i, arg) in args[1..].iter().enumerate() {
-            if arg.starts_with('-') && arg.len() > 1 && arg[1..].chars().all(|c| c.is_ascii_digit()) {
-                if let Ok(seconds) = arg[1..].parse::<u64>() {
-                    interval = Some(seconds);
-                    interval_index = Some(i + 1); // +1 because we're iterating from args[1..]
+            // Keep the minimum gap, the restart below covers anything saved in the meantime
+            let remaining = min_gap.saturating_sub(last_restart.elapsed());
+            if !remaining.is_zero() && !self.paused {
+                sleep(remaining).await;
+                while let Ok(message) = rx.try_recv() {
+                    match message {
+                        WatchMessage::Change(event) => {
+                            self.collect_change(&mut backend, event, &mut changed)
+                        }
+                        WatchMessage::Exited(status) => {
+                            if self.handle_exit(status) {
+                                break 'watch;
+                            }
+                        }
+                        WatchMessage::Retry => {}
+                        WatchMessage::Ready { run, ready } => self.handle_ready(run, ready),
+                        WatchMessage::Key(key) => {
+                            if self.handle_key(key, &mut changed) == KeyAction::Quit {
+                                break 'watch;
+                            }
+                        }
+                        WatchMessage::Shutdown => break 'watch,
+                    }
                 }
             }
-        }
 
-        // Add all args except the interval to filtered_args
-        for (i, arg) in args[1..].iter().enumerate() {
-            if Some(i + 1) != interval_index {
-                filtered_args.push(arg.clone());
+            // Changes while paused are kept for when watching resumes
+            if self.paused {
+                self.paused_changes.append(&mut changed);
+                continue;
+            }
+            if changed.is_empty() {
+                continue;
             }
+
+            let reason = format!("{} file(s) changed", changed.len());
+            self.report_changes(&changed);
+            let browsers = Reload::for_changes(&changed);
+            self.last_changes = changed;
+            self.reload(&reason, browsers).await?;
+            last_restart = Instant::now();
         }
 
-        let final_interval = interval.unwrap_or_else(|| {
-            println!("Warning: No interval specified. Default is 5 seconds.");
-            println!("Press Enter to continue or Ctrl+C to cancel...");
-            std::io::stdin().read_line(&mut String::new()).unwrap();
-            5
-        });
+        println!("{}", self.prefixed("Stopping..."));
+        self.stop_process().await;
+        Ok(())
+    }
+
+    /// Build and restart the command, keeping the running one if the build fails, then reload
+    /// browsers the way `browsers` says
+    async fn reload(&mut self, reason: &str, browsers: Reload) -> Result<()> {
+        let action = if self.options.build.is_some() { "rebuilding" } else { "restarting" };
+        println!("{}", self.prefixed(&format!("{}, {}...", reason, action)));
+
+        if self.env_changed {
+            self.env_changed = false;
+            self.env.reload();
+            println!("{}", self.prefixed("Reloaded environment files"));
+        }
+
+        // The running command keeps serving until the new code has built
+        self.retries = 0;
+        if self.run_build().await {
+            self.restart_process().await?;
+            self.schedule_reload(browsers);
+        } else if self.current_process.as_ref().is_some_and(|process| !process.exited) {
+            println!("{}", self.prefixed("Keeping the previous process running"));
+        }
+        Ok(())
+    }
+
+    /// Emit the change event and run the --on-change hook
+    fn report_changes(&self, changed: &BTreeSet<PathBuf>) {
+        let paths: Vec<String> =
+            changed.iter().map(|path| path.display().to_string()).collect();
+        self.emit("change", json!({ "paths": paths }));
 
-        Ok((command, filtered_args, final_interval))
+        if let Some(hook) = &self.options.on_change {
+            self.run_hook(hook, "change", vec![("RVM_CHANGED_PATHS", paths.join("\n"))]);
+        }
     }
 
-    pub async fn start(&mut self) -> Result<()> {
-        println!("Watching for changes... (interval: {}s)", self.interval);
-        println!("Press Ctrl+C to stop");
+    fn emit(&self, event: &str, fields: serde_json::Value) {
+        if let Some(event_log) = &self.event_log {
+            event_log.emit(self.task.as_deref(), event, fields);
+        }
+    }
 
-        let (tx, rx) = mpsc::channel();
-        
-        let mut watcher = RecommendedWatcher::new(
-            move |result: std::result::Result<Event, notify::Error>| {
-                if let Ok(event) = result {
-                    if let EventKind::Modify(_) | EventKind::Create(_) = event.kind {
-                        // Basic filtering for temp files only (gitignore is handled at directory level)
-                        let should_ignore = event.paths.iter().any(|path| {
-                            let path_str = path.to_string_lossy();
-                            path_str.ends_with(".tmp") || path_str.ends_with("~")
-                        });
-
-                        if !should_ignore {
-                            let _ = tx.send(event);
-                        }
+    /// Run a hook in the background with details of the event in RVM_* variables
+    fn run_hook(&self, hook: &str, event: &str, mut vars: Vec<(&str, String)>) {
+        vars.push(("RVM_EVENT", event.to_string()));
+        if let Some(task) = &self.task {
+            vars.push(("RVM_TASK", task.clone()));
+        }
+
+        match self.spawn(hook, None, &vars) {
+            Ok(mut process) => {
+                tokio::spawn(async move {
+                    let _ = process.child.wait().await;
+                    reap_group(process.pgid);
+                });
+            }
+            Err(e) => {
+                let warning = format!("Warning: Failed to run {} hook: {}", event, e);
+                eprintln!("{}", self.prefixed(&warning));
+            }
+        }
+    }
+
+    /// Reload browsers once the new run can serve them: when a one-shot task finishes, when the
+    /// readiness check passes, or right away
+    fn schedule_reload(&mut self, browsers: Reload) {
+        if self.livereload.is_none() {
+            return;
+        }
+        if self.oneshot || self.options.ready.is_some() {
+            self.pending_reload = Some(browsers);
+        } else {
+            self.push_reload(browsers);
+        }
+    }
+
+    fn push_reload(&self, browsers: Reload) {
+        let Some(server) = &self.livereload else {
+            return;
+        };
+        let reached = server.send(browsers);
+        if reached > 0 {
+            let message = match browsers {
+                Reload::Page => format!("↻ Reloaded {} browser(s)", reached),
+                Reload::Css => format!("↻ Swapped stylesheets in {} browser(s)", reached),
+            };
+            println!("{}", self.prefixed(&message));
+        }
+    }
+
+    /// Act on a key press. Resuming moves the changes seen while paused into `changed`
+    fn handle_key(&mut self, key: WatchKey, changed: &mut BTreeSet<PathBuf>) -> KeyAction {
+        match key {
+            WatchKey::Quit => KeyAction::Quit,
+            WatchKey::Restart => KeyAction::Restart,
+            WatchKey::Pause if self.paused => {
+                self.paused = false;
+                println!("{}", self.prefixed("Resumed watching"));
+                changed.append(&mut self.paused_changes);
+                KeyAction::Continue
+            }
+            WatchKey::Pause => {
+                self.paused = true;
+                println!("{}", self.prefixed("Paused, press p to resume"));
+                KeyAction::Continue
+            }
+            WatchKey::List => {
+                if self.last_changes.is_empty() {
+                    println!("{}", self.prefixed("No file changes yet"));
+                } else {
+                    println!("{}", self.prefixed("Last restart triggered by:"));
+                    for path in &self.last_changes {
+                        let path = path.strip_prefix(&self.root).unwrap_or(path);
+                        println!("{}", self.prefixed(&format!("  {}", path.display())));
                     }
                 }
-            },
-            Config::default(),
-        )?;
+                KeyAction::Continue
+            }
+        }
+    }
+
+    /// Wait for the next watch message, including the command exiting by itself and, when
+    /// `retry` is set, a pending restart coming due
+    async fn next_message(
+        &mut self,
+        rx: &mut mpsc::UnboundedReceiver<WatchMessage>,
+        retry: bool,
+    ) -> Option<WatchMessage> {
+        let restart_at = self.restart_at.filter(|_| retry);
+        let process = self.current_process.as_mut().filter(|process| !process.exited);
 
-        // Watch only the allowed directories (non-recursive to avoid deep nesting issues)
-        for path in &self.watch_paths {
-            if let Err(e) = watcher.watch(path, RecursiveMode::NonRecursive) {
-                eprintln!("Warning: Failed to watch {}: {}", path.display(), e);
+        let exited = async {
+            match process {
+                Some(process) => process.child.wait().await.ok(),
+                None => std::future::pending().await,
+            }
+        };
+        let retry_due = async {
+            match restart_at {
+                Some(restart_at) => sleep_until(restart_at).await,
+                None => std::future::pending().await,
             }
+        };
+
+        tokio::select! {
+            message = rx.recv() => message,
+            status = exited => Some(WatchMessage::Exited(status)),
+            _ = retry_due => Some(WatchMessage::Retry),
         }
+    }
 
-        // Start initial process
-        self.restart_process().await?;
+    /// Report how the command exited and schedule a restart if the policy asks for one. One-shot
+    /// tasks are expected to exit, services are not. Returns true if watching should stop
+    fn handle_exit(&mut self, status: Option<ExitStatus>) -> bool {
+        let Some(process) = self.current_process.as_mut() else {
+            return false;
+        };
+        process.exited = true;
+        reap_group(process.pgid);
+        if let Some(task) = process.ready_task.take() {
+            task.abort();
+        }
 
-        let mut last_restart = Instant::now();
+        let runtime = process.started.elapsed();
+        let elapsed = runtime.as_secs_f64();
+        let success = status.is_some_and(|status| status.success());
 
-        loop {
-            if let Ok(_event) = rx.try_recv() {
-                let now = Instant::now();
-                let elapsed = now.duration_since(last_restart);
-                
-                // Debounce: only restart if enough time has passed
-                if elapsed >= Duration::from_secs(self.interval) {
-                    println!("File change detected, restarting...");
-                    self.restart_process().await?;
-                    last_restart = now;
-                }
+        self.report_exit(status, runtime);
+        let report = if success && self.oneshot {
+            format!("✓ Finished in {:.1}s", elapsed)
+        } else if success {
+            format!("✓ Exited with {} after {:.1}s", describe_exit(status), elapsed)
+        } else {
+            format!("✗ Exited with {} after {:.1}s", describe_exit(status), elapsed)
+        };
+
+        if success && self.options.exit_on_success {
+            println!("{}", self.prefixed(&format!("{}, exiting", report)));
+            return true;
+        }
+
+        let restart = match self.options.restart {
+            RestartPolicy::Always => true,
+            RestartPolicy::OnFailure => !success,
+            RestartPolicy::Never => false,
+        };
+        if runtime >= HEALTHY_RUN {
+            self.retries = 0;
+        }
+
+        let next = if !restart {
+            // A finished one-shot task has nothing left to wait for
+            (!(success && self.oneshot)).then(|| "waiting for changes".to_string())
+        } else if self.retries >= self.options.max_retries {
+            Some(format!(
+                "gave up after {} restarts, waiting for changes",
+                self.retries
+            ))
+        } else {
+            let backoff = BACKOFF_START
+                .saturating_mul(2u32.saturating_pow(self.retries))
+                .min(BACKOFF_MAX);
+            self.retries += 1;
+            self.restart_at = Some(Instant::now() + backoff);
+            Some(format!(
+                "restarting in {:.1}s (attempt {}/{})",
+                backoff.as_secs_f64(),
+                self.retries,
+                self.options.max_retries
+            ))
+        };
+
+        let message = match next {
+            Some(next) => format!("{}, {}", report, next),
+            None => report,
+        };
+        println!("{}", self.prefixed(&message));
+
+        if success
+            && self.oneshot
+            && let Some(browsers) = self.pending_reload.take()
+        {
+            self.push_reload(browsers);
+        }
+        false
+    }
+
+    /// Report the readiness of the current run, results of earlier runs are stale
+    fn handle_ready(&mut self, run: u64, ready: bool) {
+        let (Some(process), Some(check)) = (&self.current_process, &self.options.ready) else {
+            return;
+        };
+        if process.run != run || process.exited {
+            return;
+        }
+
+        let elapsed = process.started.elapsed();
+        self.emit(
+            "ready",
+            json!({ "ready": ready, "check": check.describe(), "ms": elapsed.as_millis() as u64 }),
+        );
+
+        let elapsed = elapsed.as_secs_f64();
+        let message = if ready {
+            format!("✓ Ready in {:.1}s ({})", elapsed, check.describe())
+        } else {
+            format!("✗ Not ready after {:.1}s ({}), still running", elapsed, check.describe())
+        };
+        println!("{}", self.prefixed(&message));
+
+        if ready && let Some(browsers) = self.pending_reload.take() {
+            self.push_reload(browsers);
+        }
+    }
+
+    /// Update the watched directories for an event and record its paths as changed
+    fn collect_change(
+        &mut self,
+        backend: &mut WatchBackend,
+        mut event: Event,
+        changed: &mut BTreeSet<PathBuf>,
+    ) {
+        self.track_directories(backend, &mut event);
+        // Events written to a log inside the watched tree would otherwise restart the command
+        // over and over
+        if let Some(log) = &self.event_log {
+            event.paths.retain(|path| !is_event_log(path, log.path()));
+        }
+        changed.extend(event.paths);
+    }
+
+    /// Emit the exit event and run the --on-exit hook for a command that exited or was stopped
+    fn report_exit(&self, status: Option<ExitStatus>, runtime: Duration) {
+        let code = status.and_then(|status| status.code());
+        let signal = status.and_then(|status| status.signal());
+        self.emit(
+            "exit",
+            json!({
+                "code": code,
+                "signal": signal,
+                "success": status.is_some_and(|status| status.success()),
+                "runtime_ms": runtime.as_millis() as u64,
+            }),
+        );
+        if let Some(hook) = &self.options.on_exit {
+            let vars = vec![
+                ("RVM_EXIT_CODE", code.map(|code| code.to_string()).unwrap_or_default()),
+                ("RVM_EXIT_SIGNAL", signal.map(|signal| signal.to_string()).unwrap_or_default()),
+            ];
+            self.run_hook(hook, "exit", vars);
+        }
+    }
+
+    /// Stop the running command and everything it started
+    async fn stop_process(&mut self) {
+        if let Some(process) = self.current_process.take() {
+            // A command that exited by itself was already reported
+            let exited = process.exited;
+            let started = process.started;
+            let status = self.terminate(process).await;
+            if !exited {
+                self.report_exit(status, started.elapsed());
             }
-            
-            sleep(Duration::from_millis(100)).await;
         }
     }
 
-    async fn restart_process(&mut self) -> Result<()> {
-        // Kill existing process
-        if let Some(mut child) = self.current_process.take() {
-            let _ = child.kill().await;
-            let _ = child.wait().await;
+    /// Send the stop signal to a process group, then SIGKILL whatever is still running after the
+    /// grace period. Returns how the command itself exited, if it could be reaped
+    async fn terminate(&self, mut process: RunningProcess) -> Option<ExitStatus> {
+        let pgid = process.pgid;
+
+        // Even if the shell already exited, processes it started in the background may remain
+        signal_group(pgid, self.options.stop_signal.as_raw());
+        if Self::wait_for_group(&mut process.child, pgid, self.options.grace_period).await {
+            return process.child.try_wait().ok().flatten();
         }
 
-        // Build full command string
-        let full_command = if self.args.is_empty() {
-            self.command.clone()
+        println!(
+            "{}",
+            self.prefixed(&format!(
+                "Process did not stop within {}ms, killing it",
+                self.options.grace_period.as_millis()
+            ))
+        );
+        signal_group(pgid, libc::SIGKILL);
+        Self::wait_for_group(&mut process.child, pgid, Duration::from_secs(1)).await;
+        process.child.try_wait().ok().flatten()
+    }
+
+    /// Wait until every process in the group has exited and been reaped, returns false if some
+    /// are still running after `limit`
+    async fn wait_for_group(child: &mut Child, pgid: u32, limit: Duration) -> bool {
+        let deadline = Instant::now() + limit;
+        let _ = timeout(limit, child.wait()).await;
+
+        // Children of the shell (e.g. the server started by `cargo run`) may still be shutting down.
+        // The shell is waited for first, so its status isn't lost to reap_group
+        loop {
+            let _ = child.try_wait();
+            reap_group(pgid);
+            if !signal_group(pgid, 0) {
+                return true;
+            }
+            if Instant::now() >= deadline {
+                return false;
+            }
+            sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// Run the build step, if any, and report how it went. Returns false if it failed or was
+    /// interrupted, in which case the command must not be (re)started
+    async fn run_build(&self) -> bool {
+        let Some(build) = &self.options.build else {
+            return true;
+        };
+
+        println!("{}", self.prefixed(&format!("Building: {}", build)));
+        let mut process = match self.spawn(build, None, &[]) {
+            Ok(process) => process,
+            Err(e) => {
+                println!("{}", self.prefixed(&format!("✗ Build failed to start: {}", e)));
+                return false;
+            }
+        };
+
+        // Changes saved during the build stay queued and trigger another build afterwards.
+        // Ctrl+C is also forwarded to the watch loop, here it only has to stop the build
+        let status = tokio::select! {
+            status = process.child.wait() => status.ok(),
+            _ = tokio::signal::ctrl_c() => {
+                self.terminate(process).await;
+                return false;
+            }
+        };
+        reap_group(process.pgid);
+
+        let elapsed = process.started.elapsed().as_secs_f64();
+        let message = if status.is_some_and(|status| status.success()) {
+            format!("✓ Build finished in {:.1}s", elapsed)
         } else {
-            format!("{} {}", self.command, self.args.join(" "))
+            format!("✗ Build failed with {} after {:.1}s", describe_exit(status), elapsed)
         };
+        println!("{}", self.prefixed(&message));
+        status.is_some_and(|status| status.success())
+    }
 
-        println!("Running: {}", full_command);
+    async fn restart_process(&mut self) -> Result<()> {
+        // A one-shot build step runs to completion, changes saved meanwhile trigger the next run.
+        // Ctrl+C is also forwarded to the watch loop, here it only has to stop waiting
+        if self.oneshot
+            && let Some(process) = self.current_process.as_mut()
+        {
+            tokio::select! {
+                _ = process.child.wait() => {}
+                _ = tokio::signal::ctrl_c() => return Ok(()),
+            }
+        }
+        self.stop_process().await;
+        self.restart_at = None;
+        self.pending_reload = None;
+
+        println!("{}", self.prefixed(&format!("Running: {}", self.command)));
+        let log = self.options.ready.as_ref().and_then(ReadyCheck::log_match);
+        let mut process = self.spawn(&self.command, log.clone(), &[])?;
+
+        self.runs += 1;
+        process.run = self.runs;
+        self.emit("restart", json!({ "run": self.runs, "command": self.command }));
+        if let (Some(check), Some(events)) = (self.options.ready.clone(), self.events.clone()) {
+            let run = self.runs;
+            let limit = self.options.ready_timeout;
+            process.ready_task = Some(tokio::spawn(async move {
+                let ready = timeout(limit, check.wait(log)).await.is_ok();
+                let _ = events.send(WatchMessage::Ready { run, ready });
+            }));
+        }
 
+        self.current_process = Some(process);
+        Ok(())
+    }
+
+    /// Start a shell command line in its own process group with extra variables, `log` sees its
+    /// output lines
+    fn spawn(
+        &self,
+        command: &str,
+        log: Option<LogMatch>,
+        vars: &[(&str, String)],
+    ) -> Result<RunningProcess> {
         // Run through shell to handle &&, ||, pipes, etc.
         let mut cmd = Command::new("/bin/sh");
         cmd.arg("-c");
-        cmd.arg(&full_command);
-        cmd.stdout(Stdio::inherit());
-        cmd.stderr(Stdio::inherit());
+        cmd.arg(command);
+        cmd.current_dir(&self.root);
+        self.env.apply(&mut cmd);
+        cmd.envs(vars.iter().map(|(key, value)| (key, value)));
+        // Output of labelled tasks is prefixed line by line, log readiness checks read it too
+        if self.label.is_some() || log.is_some() {
+            cmd.stdout(Stdio::piped());
+            cmd.stderr(Stdio::piped());
+        } else {
+            cmd.stdout(Stdio::inherit());
+            cmd.stderr(Stdio::inherit());
+        }
+        // Keys typed into the terminal are meant for rvm
+        if self.keys.is_some() {
+            cmd.stdin(Stdio::null());
+        }
+        // New process group so the whole tree can be signalled on restart. It stays a background
+        // group, so Ctrl+C reaches rvm and reading the terminal stops the command (SIGTTIN)
+        cmd.process_group(0);
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| RvmError::CommandExecutionFailed(e.to_string()))?;
+
+        if let Some(stdout) = child.stdout.take() {
+            forward_output(stdout, self.label.clone(), false, log.clone());
+        }
+        if let Some(stderr) = child.stderr.take() {
+            forward_output(stderr, self.label.clone(), true, log);
+        }
+
+        // The shell leads its new process group, so the group id is its pid
+        let pgid = child.id().unwrap_or_default();
+        Ok(RunningProcess {
+            child,
+            pgid,
+            started: Instant::now(),
+            exited: false,
+            run: 0,
+            ready_task: None,
+        })
+    }
+}
+
+/// The notify watcher in use: inotify, or polling where inotify is out of watches or doesn't see
+/// changes (NFS, Docker bind mounts)
+struct WatchBackend {
+    watcher: Box<dyn Watcher + Send>,
+    tx: mpsc::UnboundedSender<WatchMessage>,
+    polling: bool,
+}
+
+impl WatchBackend {
+    /// inotify watcher, gives the sender back on failure so polling can be used instead
+    fn inotify(
+        tx: mpsc::UnboundedSender<WatchMessage>,
+    ) -> std::result::Result<Self, (mpsc::UnboundedSender<WatchMessage>, notify::Error)> {
+        match RecommendedWatcher::new(event_handler(tx.clone()), Config::default()) {
+            Ok(watcher) => Ok(WatchBackend {
+                watcher: Box::new(watcher),
+                tx,
+                polling: false,
+            }),
+            Err(e) => Err((tx, e)),
+        }
+    }
+
+    fn poll(tx: mpsc::UnboundedSender<WatchMessage>, interval: Duration) -> Result<Self> {
+        let config = Config::default().with_poll_interval(interval);
+        let watcher = PollWatcher::new(event_handler(tx.clone()), config)?;
+        Ok(WatchBackend {
+            watcher: Box::new(watcher),
+            tx,
+            polling: true,
+        })
+    }
+
+    fn is_polling(&self) -> bool {
+        self.polling
+    }
+
+    fn watch(&mut self, dir: &Path) -> notify::Result<()> {
+        self.watcher.watch(dir, RecursiveMode::NonRecursive)
+    }
+
+    fn unwatch(&mut self, dir: &Path) -> notify::Result<()> {
+        self.watcher.unwatch(dir)
+    }
+
+    /// Replace inotify with polling for all `dirs`, returns the ones that failed
+    fn switch_to_polling(&mut self, dirs: &BTreeSet<PathBuf>) -> Vec<(PathBuf, notify::Error)> {
+        let mut failed = Vec::new();
+        match Self::poll(self.tx.clone(), FALLBACK_POLL_INTERVAL) {
+            // Dropping the inotify watcher releases its watches
+            Ok(backend) => *self = backend,
+            Err(e) => {
+                eprintln!("Warning: Failed to start polling: {}", e);
+                return failed;
+            }
+        }
+
+        for dir in dirs {
+            if let Err(e) = self.watch(dir) {
+                failed.push((dir.clone(), e));
+            }
+        }
+        failed
+    }
+}
+
+/// Whether a changed path is the event log, which may be reached through a symlinked directory
+fn is_event_log(path: &Path, log: &Path) -> bool {
+    path == log
+        || (path.file_name() == log.file_name()
+            && std::fs::canonicalize(path).is_ok_and(|canonical| canonical == log))
+}
+
+/// Forward relevant notify events to the watch loop
+fn event_handler(
+    tx: mpsc::UnboundedSender<WatchMessage>,
+) -> impl FnMut(notify::Result<Event>) + Send + 'static {
+    move |result| {
+        // Deletions and renames (reported as Modify) count as changes too
+        if let Ok(event) = result
+            && let EventKind::Modify(_) | EventKind::Create(_) | EventKind::Remove(_) = event.kind
+        {
+            // Ignore rules are applied in the event loop, which knows the watched directories
+            let _ = tx.send(WatchMessage::Change(event));
+        }
+    }
+}
 
-        match cmd.spawn() {
-            Ok(child) => {
-                self.current_process = Some(child);
-                Ok(())
+/// Whether inotify ran out of watches (ENOSPC)
+fn is_watch_limit(error: &notify::Error) -> bool {
+    match &error.kind {
+        notify::ErrorKind::MaxFilesWatch => true,
+        notify::ErrorKind::Io(e) => e.raw_os_error() == Some(libc::ENOSPC),
+        _ => false,
+    }
+}
+
+/// Explain the inotify watch limit, with the current value and one that fits `needed` watches
+fn watch_limit_report(needed: usize) -> String {
+    let current = std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok());
+
+    let limit = match current {
+        Some(current) => format!(
+            "fs.inotify.max_user_watches is {}, this project needs {} on top of the watches of \
+             other programs",
+            current, needed
+        ),
+        None => format!("this project needs {} watches", needed),
+    };
+    let suggested = (current.unwrap_or(0) + needed).next_power_of_two().max(524288);
+
+    format!(
+        "Warning: inotify watch limit reached ({}). Polling every {}ms instead.\n\
+         To keep using inotify, raise the limit: sudo sysctl fs.inotify.max_user_watches={} \
+         (add it to /etc/sysctl.d/ to keep it after a reboot)",
+        limit,
+        FALLBACK_POLL_INTERVAL.as_millis(),
+        suggested
+    )
+}
+
+/// Print a child's output line by line, behind the task label if any, and pass each line to a
+/// log readiness check
+fn forward_output(
+    reader: impl AsyncRead + Unpin + Send + 'static,
+    label: Option<String>,
+    to_stderr: bool,
+    log: Option<LogMatch>,
+) {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(reader).split(b'\n');
+        while let Ok(Some(line)) = lines.next_segment().await {
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches('\r');
+            if let Some(log) = &log {
+                log.check(line);
+            }
+
+            let line = match &label {
+                Some(label) => format!("{} {}", label, line),
+                None => line.to_string(),
+            };
+            if to_stderr {
+                eprintln!("{}", line);
+            } else {
+                println!("{}", line);
             }
-            Err(e) => Err(RvmError::CommandExecutionFailed(e.to_string())),
         }
+    });
+}
+
+/// Describe an exit status, e.g. "exit code 1" or "signal 9"
+fn describe_exit(status: Option<ExitStatus>) -> String {
+    match status {
+        Some(status) => match (status.code(), status.signal()) {
+            (Some(code), _) => format!("exit code {}", code),
+            (None, Some(signal)) => format!("signal {}", signal),
+            (None, None) => "unknown status".to_string(),
+        },
+        None => "unknown status".to_string(),
     }
-}
\ No newline at end of file
+}
+
+/// Send a signal to every process in a process group, returns false if the group is gone
+fn signal_group(pgid: u32, signal: libc::c_int) -> bool {
+    // SAFETY: killpg has no memory safety requirements, a stale group id only yields ESRCH
+    unsafe { libc::killpg(pgid as libc::pid_t, signal) == 0 }
+}
+
+/// Reap exited processes of a group that were re-parented to rvm
+fn reap_group(pgid: u32) {
+    let mut status = 0;
+    // SAFETY: waitpid only writes the exit status of a reaped process into `status`
+    while unsafe { libc::waitpid(-(pgid as libc::pid_t), &mut status, libc::WNOHANG) } > 0 {}
+}
+
+/// Quote an argument for /bin/sh, leaving plain words readable
+fn shell_quote(arg: &str) -> String {
+    let is_plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=:,+@%".contains(c));
+
+    if is_plain {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn trailing_seconds_set_the_legacy_delay() {
+        let (command, delay) =
+            FileWatcher::parse_command(args(&["ls", "-5"]), None, false).unwrap();
+        assert_eq!(command, "ls");
+        assert_eq!(delay, 5);
+    }
+
+    #[test]
+    fn arguments_after_separator_are_kept() {
+        let (command, delay) =
+            FileWatcher::parse_command(args(&["tail", "-5"]), None, true).unwrap();
+        assert_eq!(command, "tail -5");
+        assert_eq!(delay, 0);
+    }
+
+    #[test]
+    fn delay_flag_keeps_trailing_argument() {
+        let (command, delay) =
+            FileWatcher::parse_command(args(&["ls", "-1"]), Some(2), false).unwrap();
+        assert_eq!(command, "ls -1");
+        assert_eq!(delay, 2);
+    }
+}